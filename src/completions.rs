@@ -0,0 +1,48 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::Opts;
+
+/// Writes the completion script of `shell`, which completes the values of options such as
+/// `--db-lib` and `--template` as well as the keys of `salvo config`.
+pub fn write(shell: Shell, out: &mut dyn Write) -> Result<()> {
+    // clap_complete panics on write errors, such as a closed pipe, so the script is buffered.
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Opts::command(), "salvo", &mut script);
+    out.write_all(&script)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+
+    use super::write;
+
+    fn script(shell: Shell) -> String {
+        let mut out = Vec::new();
+        write(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_completions_offer_subcommands_and_option_values() {
+        let bash = script(Shell::Bash);
+        assert!(bash.contains("_salvo()"));
+        assert!(bash.contains("--db-lib"));
+        assert!(bash.contains("sqlx seaorm diesel rbatis mongodb"));
+        assert!(bash.contains("classic"));
+        assert!(bash.contains("author.name"), "config keys complete");
+
+        for shell in [Shell::Zsh, Shell::Fish, Shell::Elvish, Shell::PowerShell] {
+            let script = script(shell);
+            assert!(script.contains("completions"), "{shell}");
+        }
+        // elvish and PowerShell scripts only complete subcommands and flags.
+        assert!(script(Shell::Zsh).contains("seaorm"));
+        assert!(script(Shell::Fish).contains("seaorm"));
+    }
+}
@@ -0,0 +1,374 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rust_i18n::t;
+use serde::Deserialize;
+use serde_json::json;
+use toml::{Table, Value};
+
+use crate::hooks::Hook;
+use crate::printer::{self, success};
+use crate::templates::TemplateKind;
+use crate::templates::classic::selection::{DbLib, DbType};
+use crate::{i18n, utils};
+
+/// Defaults for every invocation, read from `~/.config/salvo/config.toml`. Flags override them.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Language of the CLI messages.
+    pub lang: Option<String>,
+    /// Language of the comments and text in generated projects.
+    pub code_lang: Option<String>,
+    pub db_lib: Option<DbLib>,
+    pub db_type: Option<DbType>,
+    pub template: Option<TemplateKind>,
+    pub request_limits: Option<bool>,
+    pub http3: Option<bool>,
+    pub author: Author,
+    pub update_check: Option<UpdateCheck>,
+    /// The crates.io API, or a mirror of it, asked for new salvo-cli releases.
+    pub registry: Option<String>,
+    /// The proxy used for update checks, on top of the `HTTPS_PROXY` environment variable.
+    pub proxy: Option<String>,
+    /// Whether `salvo new` creates a git repository.
+    pub git_init: Option<bool>,
+    pub hooks: Hooks,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Author {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Author {
+    /// The author as Cargo writes it, `name <email>`.
+    pub fn to_cargo(&self) -> Option<String> {
+        match (&self.name, &self.email) {
+            (Some(name), Some(email)) => Some(format!("{name} <{email}>")),
+            (Some(name), None) => Some(name.clone()),
+            (None, Some(email)) => Some(format!("<{email}>")),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The steps `salvo new` runs once the files are written, `None` keeping the default of a hook.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    pub fmt: Option<bool>,
+    pub lockfile: Option<bool>,
+    pub check: Option<bool>,
+    pub commit: Option<bool>,
+}
+
+impl Hooks {
+    pub fn get(&self, hook: Hook) -> Option<bool> {
+        match hook {
+            Hook::Fmt => self.fmt,
+            Hook::Lockfile => self.lockfile,
+            Hook::Check => self.check,
+            Hook::Commit => self.commit,
+        }
+    }
+}
+
+/// How often `salvo new` looks for a new release, once a day by default.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateCheck {
+    /// On every run, ignoring the cached result.
+    Always,
+    Never,
+}
+
+/// The keys `salvo config` reads and writes, `author.name` being `name` in the `[author]` table.
+pub const KEYS: [&str; 17] = [
+    "lang",
+    "code_lang",
+    "db_lib",
+    "db_type",
+    "template",
+    "request_limits",
+    "http3",
+    "author.name",
+    "author.email",
+    "update_check",
+    "registry",
+    "proxy",
+    "git_init",
+    "hooks.fmt",
+    "hooks.lockfile",
+    "hooks.check",
+    "hooks.commit",
+];
+
+/// `$XDG_CONFIG_HOME/salvo/config.toml`, falling back to `~/.config`, or `%APPDATA%` on Windows.
+fn path() -> Result<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    let dir = var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| var("APPDATA").map(PathBuf::from))
+        .ok_or_else(|| anyhow::anyhow!(t!("error_config_no_home")))?;
+    Ok(dir.join("salvo").join("config.toml"))
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    if !path.exists() {
+        return Ok(Table::new());
+    }
+    fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(content.parse::<Table>()?))
+        .with_context(|| t!("error_config_read", path = path.to_string_lossy()))
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if !KEYS.contains(&key) {
+        anyhow::bail!(t!(
+            "error_config_unknown_key",
+            key = key,
+            keys = KEYS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// The table holding `key` and the key within it.
+fn locate<'a>(table: &'a Table, key: &'a str) -> Option<(&'a Table, &'a str)> {
+    match key.split_once('.') {
+        Some((section, key)) => Some((table.get(section)?.as_table()?, key)),
+        None => Some((table, key)),
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The user config, empty when there is no config file.
+pub fn load() -> Result<Config> {
+    load_from(&path()?)
+}
+
+fn load_from(path: &Path) -> Result<Config> {
+    Table::try_into(read_table(path)?)
+        .with_context(|| t!("error_config_read", path = path.to_string_lossy()))
+}
+
+fn get_in(path: &Path, key: &str) -> Result<Option<String>> {
+    check_key(key)?;
+    let table = read_table(path)?;
+    Ok(locate(&table, key).and_then(|(table, key)| table.get(key).map(display)))
+}
+
+fn set_in(path: &Path, key: &str, value: &str) -> Result<()> {
+    check_key(key)?;
+    let invalid = || t!("error_config_invalid_value", key = key, value = value);
+    let value = match key {
+        "request_limits" | "http3" | "git_init" | "hooks.fmt" | "hooks.lockfile"
+        | "hooks.check" | "hooks.commit" => Value::Boolean(value.parse().with_context(invalid)?),
+        "lang" | "code_lang" => Value::String(
+            i18n::parse_code_lang(value).map_err(|e| anyhow::anyhow!(e).context(invalid()))?,
+        ),
+        "registry" | "proxy" => {
+            reqwest::Url::parse(value).with_context(invalid)?;
+            Value::String(value.to_owned())
+        }
+        _ => Value::String(value.to_owned()),
+    };
+    let mut table = read_table(path)?;
+    match key.split_once('.') {
+        Some((section, key)) => {
+            let section = table
+                .entry(section)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(section) = section {
+                section.insert(key.to_owned(), value);
+            }
+        }
+        None => {
+            table.insert(key.to_owned(), value);
+        }
+    }
+    Table::try_into::<Config>(table.clone()).with_context(invalid)?;
+    write(path, &table)
+}
+
+fn unset_in(path: &Path, key: &str) -> Result<()> {
+    check_key(key)?;
+    let mut table = read_table(path)?;
+    match key.split_once('.') {
+        Some((section, key)) => {
+            if let Some(Value::Table(section)) = table.get_mut(section) {
+                section.remove(key);
+            }
+        }
+        None => {
+            table.remove(key);
+        }
+    }
+    write(path, &table)
+}
+
+fn list_in(path: &Path) -> Result<Vec<(&'static str, String)>> {
+    let table = read_table(path)?;
+    Ok(KEYS
+        .into_iter()
+        .filter_map(|key| {
+            let (table, name) = locate(&table, key)?;
+            Some((key, display(table.get(name)?)))
+        })
+        .collect())
+}
+
+fn write(path: &Path, table: &Table) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        utils::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string(table)?)?;
+    Ok(())
+}
+
+/// Prints the value of `key`, nothing when it is not set.
+pub fn get(key: &str) -> Result<()> {
+    if let Some(value) = get_in(&path()?, key)? {
+        print_value(key, &value, &value);
+    }
+    Ok(())
+}
+
+pub fn set(key: &str, value: &str) -> Result<()> {
+    let path = path()?;
+    set_in(&path, key, value)?;
+    success(t!(
+        "config_set",
+        key = key,
+        value = value,
+        path = path.to_string_lossy()
+    ));
+    Ok(())
+}
+
+pub fn unset(key: &str) -> Result<()> {
+    let path = path()?;
+    unset_in(&path, key)?;
+    success(t!("config_unset", key = key, path = path.to_string_lossy()));
+    Ok(())
+}
+
+/// Prints every key that is set as `key = value`.
+pub fn list() -> Result<()> {
+    for (key, value) in list_in(&path()?)? {
+        print_value(key, &value, &format!("{key} = {value}"));
+    }
+    Ok(())
+}
+
+/// Prints `text`, or a `config_value` event in JSON mode.
+fn print_value(key: &str, value: &str, text: &str) {
+    if printer::is_json() {
+        printer::event("config_value", json!({ "key": key, "value": value }));
+    } else {
+        println!("{text}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Author, Config, UpdateCheck, get_in, list_in, load_from, set_in, unset_in};
+    use crate::templates::classic::selection::DbLib;
+    use crate::utils::unique_temp_dir;
+
+    #[test]
+    fn test_config_keys_are_set_validated_and_listed() {
+        let dir = unique_temp_dir("config");
+        let path = dir.join("salvo/config.toml");
+        assert_eq!(load_from(&path).unwrap(), Config::default());
+
+        set_in(&path, "db_lib", "diesel").unwrap();
+        set_in(&path, "lang", "zh").unwrap();
+        set_in(&path, "author.name", "Ada").unwrap();
+        set_in(&path, "author.email", "ada@example.com").unwrap();
+        set_in(&path, "update_check", "never").unwrap();
+        set_in(&path, "git_init", "false").unwrap();
+        set_in(&path, "hooks.check", "true").unwrap();
+        set_in(&path, "http3", "true").unwrap();
+        assert!(set_in(&path, "hooks.fmt", "yes").is_err());
+        assert!(set_in(&path, "db_lib", "sqlite").is_err());
+        assert!(set_in(&path, "git_init", "maybe").is_err());
+        assert!(set_in(&path, "code_lang", "xx").is_err());
+        assert!(set_in(&path, "colour", "red").is_err());
+        assert!(set_in(&path, "registry", "crates mirror").is_err());
+        set_in(&path, "registry", "http://127.0.0.1:8080/").unwrap();
+
+        let config = load_from(&path).unwrap();
+        assert_eq!(config.db_lib, Some(DbLib::Diesel));
+        assert_eq!(config.lang.as_deref(), Some("zh_CN"));
+        assert_eq!(config.update_check, Some(UpdateCheck::Never));
+        assert_eq!(config.git_init, Some(false));
+        assert_eq!(config.hooks.check, Some(true));
+        assert_eq!(config.hooks.fmt, None);
+        assert_eq!(config.http3, Some(true));
+        assert_eq!(
+            config.author.to_cargo().as_deref(),
+            Some("Ada <ada@example.com>")
+        );
+        assert_eq!(
+            get_in(&path, "author.name").unwrap().as_deref(),
+            Some("Ada")
+        );
+        assert_eq!(get_in(&path, "git_init").unwrap().as_deref(), Some("false"));
+        assert_eq!(get_in(&path, "db_type").unwrap(), None);
+        assert!(get_in(&path, "colour").is_err());
+
+        unset_in(&path, "author.email").unwrap();
+        unset_in(&path, "db_lib").unwrap();
+        let keys = list_in(&path)
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            [
+                "lang",
+                "http3",
+                "author.name",
+                "update_check",
+                "registry",
+                "git_init",
+                "hooks.check"
+            ]
+        );
+
+        fs::write(&path, "db_lib = 1").unwrap();
+        assert!(load_from(&path).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_author_is_written_the_way_cargo_expects() {
+        let author = |name: Option<&str>, email: Option<&str>| Author {
+            name: name.map(str::to_owned),
+            email: email.map(str::to_owned),
+        };
+        assert_eq!(author(None, None).to_cargo(), None);
+        assert_eq!(author(Some("Ada"), None).to_cargo().as_deref(), Some("Ada"));
+        assert_eq!(
+            author(None, Some("ada@example.com")).to_cargo().as_deref(),
+            Some("<ada@example.com>")
+        );
+    }
+}
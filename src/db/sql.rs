@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use reqwest::Url;
+use rusqlite::Connection;
+use rusqlite::types::ValueRef;
+
+use crate::generate::introspect::{Source, mysql_command, psql_command, run_client};
+
+/// A connection that runs migration scripts, SQLite in process and the others through their CLI clients.
+pub enum Database {
+    Sqlite(Connection),
+    Postgres(String),
+    Mysql(Url),
+}
+
+impl Database {
+    pub fn connect(source: &Source) -> Result<Self> {
+        Ok(match source {
+            Source::Sqlite(path) => {
+                Self::Sqlite(Connection::open(path).with_context(|| {
+                    format!("failed to open sqlite database `{}`", path.display())
+                })?)
+            }
+            Source::Postgres(url) => Self::Postgres(url.clone()),
+            Source::Mysql(url) => Self::Mysql(url.clone()),
+        })
+    }
+
+    pub fn is_postgres(&self) -> bool {
+        matches!(self, Self::Postgres(_))
+    }
+
+    pub fn is_mysql(&self) -> bool {
+        matches!(self, Self::Mysql(_))
+    }
+
+    /// Runs a script in a single transaction where the database supports transactional DDL.
+    pub fn execute(&mut self, sql: &str) -> Result<()> {
+        match self {
+            Self::Sqlite(conn) => {
+                let tx = conn.transaction()?;
+                tx.execute_batch(sql)?;
+                tx.commit()?;
+            }
+            Self::Postgres(url) => {
+                let mut cmd = psql_command(url);
+                cmd.arg("--single-transaction").args(["-c", sql]);
+                run_client(cmd, "psql")?;
+            }
+            Self::Mysql(url) => {
+                run_client(mysql_command(url, sql), "mysql")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs a query and returns its rows with every value as text.
+    pub fn query(&mut self, sql: &str) -> Result<Vec<Vec<String>>> {
+        let output = match self {
+            Self::Sqlite(conn) => {
+                let mut stmt = conn.prepare(sql)?;
+                let count = stmt.column_count();
+                return stmt
+                    .query_map([], |row| {
+                        (0..count)
+                            .map(|index| {
+                                Ok(match row.get_ref(index)? {
+                                    ValueRef::Null => String::new(),
+                                    ValueRef::Integer(value) => value.to_string(),
+                                    ValueRef::Real(value) => value.to_string(),
+                                    ValueRef::Text(value) | ValueRef::Blob(value) => {
+                                        String::from_utf8_lossy(value).into_owned()
+                                    }
+                                })
+                            })
+                            .collect()
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+                    .map_err(Into::into);
+            }
+            Self::Postgres(url) => {
+                let mut cmd = psql_command(url);
+                cmd.args(["-A", "-t", "-F", "\t", "-c", sql]);
+                run_client(cmd, "psql")?
+            }
+            Self::Mysql(url) => run_client(mysql_command(url, sql), "mysql")?,
+        };
+        Ok(output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split('\t').map(str::to_owned).collect())
+            .collect())
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Result;
+use rust_i18n::t;
+use semver::Version;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::printer::{self, gray, success, warning};
+use crate::templates::classic;
+use crate::templates::classic::selection::{DbLib, DbType};
+use crate::{manifest, utils};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    /// Something a part of the project needs, such as a migration CLI.
+    Warning,
+    /// Something the project cannot be built without.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Check {
+    name: String,
+    status: Status,
+    detail: String,
+    /// How to fix a failed check.
+    fix: Option<String>,
+}
+
+impl Check {
+    fn new(name: &str, status: Status, detail: impl Into<String>, fix: Option<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            status,
+            detail: detail.into(),
+            fix,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self.status {
+            Status::Ok => "✔",
+            Status::Warning => "⚠",
+            Status::Error => "✘",
+        }
+    }
+}
+
+/// Runs a program and returns its stdout, `None` when it is missing or fails.
+type Probe<'a> = &'a dyn Fn(&str, &[&str]) -> Option<String>;
+
+fn run_probe(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The first version in a tool's `--version` output, such as `rustc 1.94.0 (4a4ef493e 2026-03-02)`
+/// or `git version 2.43.0.windows.1`. Versions like `1.94` get a zero patch.
+fn parse_version(output: &str) -> Option<Version> {
+    output.split_whitespace().find_map(|token| {
+        let numbers: Vec<u64> = token
+            .split('.')
+            .map_while(|part| part.parse().ok())
+            .take(3)
+            .collect();
+        match numbers[..] {
+            [major, minor] => Some(Version::new(major, minor, 0)),
+            [major, minor, patch] => Some(Version::new(major, minor, patch)),
+            _ => None,
+        }
+    })
+}
+
+/// Checks that a toolchain program is installed and at least `required`.
+fn check_toolchain(probe: Probe, program: &str, required: &Version) -> Check {
+    let fix = || Some("rustup update stable".to_owned());
+    let Some(output) = probe(program, &["--version"]) else {
+        let fix = Some("https://rustup.rs".to_owned());
+        return Check::new(program, Status::Error, t!("doctor_not_found"), fix);
+    };
+    match parse_version(&output) {
+        Some(version) if version < *required => {
+            let detail = t!("doctor_too_old", version = version, required = required);
+            Check::new(program, Status::Error, detail, fix())
+        }
+        Some(version) => Check::new(program, Status::Ok, version.to_string(), None),
+        None => Check::new(program, Status::Warning, output.trim(), fix()),
+    }
+}
+
+/// Checks for an optional program, `fix` installing it.
+fn check_program(probe: Probe, name: &str, program: &str, fix: String) -> Check {
+    match probe(program, &["--version"]) {
+        Some(output) => {
+            let detail =
+                parse_version(&output).map_or_else(|| output.trim().to_owned(), |v| v.to_string());
+            Check::new(name, Status::Ok, detail, None)
+        }
+        None => Check::new(name, Status::Warning, t!("doctor_not_found"), Some(fix)),
+    }
+}
+
+/// The database CLI the project's README points to, with its install command.
+fn db_cli(db_lib: DbLib, db_type: DbType) -> Option<(&'static str, &'static str, String)> {
+    match db_lib {
+        DbLib::Sqlx => Some((
+            "sqlx-cli",
+            "sqlx",
+            format!("cargo install sqlx-cli --no-default-features --features rustls,{db_type}"),
+        )),
+        DbLib::SeaOrm => Some((
+            "sea-orm-cli",
+            "sea-orm-cli",
+            "cargo install sea-orm-cli".into(),
+        )),
+        DbLib::Diesel => {
+            let feature = match db_type {
+                DbType::Postgres => "postgres",
+                DbType::Mysql => "mysql",
+                _ => "sqlite-bundled",
+            };
+            Some((
+                "diesel_cli",
+                "diesel",
+                format!("cargo install diesel_cli --no-default-features --features {feature}"),
+            ))
+        }
+        DbLib::Mongodb => Some((
+            "mongosh",
+            "mongosh",
+            "https://www.mongodb.com/try/download/shell".into(),
+        )),
+        DbLib::Rbatis => None,
+    }
+}
+
+/// A native library a Diesel backend links against.
+struct Library {
+    name: &'static str,
+    pkg_config: &'static str,
+    /// The variable the `-sys` crate reads the library folder from.
+    dir_var: &'static str,
+    files: &'static [&'static str],
+    apt: &'static str,
+    brew: &'static str,
+}
+
+const LIBPQ: Library = Library {
+    name: "libpq",
+    pkg_config: "libpq",
+    dir_var: "PQ_LIB_DIR",
+    files: &["libpq.so", "libpq.dylib", "libpq.a", "libpq.lib"],
+    apt: "sudo apt install libpq-dev",
+    brew: "brew install libpq",
+};
+
+const LIBMYSQLCLIENT: Library = Library {
+    name: "libmysqlclient",
+    pkg_config: "mysqlclient",
+    dir_var: "MYSQLCLIENT_LIB_DIR",
+    files: &[
+        "libmysqlclient.so",
+        "libmysqlclient.dylib",
+        "libmysqlclient.a",
+        "mysqlclient.lib",
+    ],
+    apt: "sudo apt install libmysqlclient-dev",
+    brew: "brew install mysql-client",
+};
+
+/// Where the libraries are installed by the usual package managers.
+const LIBRARY_DIRS: [&str; 10] = [
+    "/usr/lib",
+    "/usr/lib64",
+    "/usr/local/lib",
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/opt/homebrew/lib",
+    "/opt/homebrew/opt/libpq/lib",
+    "/opt/homebrew/opt/mysql-client/lib",
+    "/usr/local/opt/libpq/lib",
+    "/usr/local/opt/mysql-client/lib",
+];
+
+fn check_library(probe: Probe, library: &Library, dirs: &[PathBuf]) -> Check {
+    let found = probe("pkg-config", &["--libs", library.pkg_config]).is_some()
+        || dirs
+            .iter()
+            .any(|dir| library.files.iter().any(|file| dir.join(file).is_file()));
+    if found {
+        return Check::new(library.name, Status::Ok, t!("doctor_library_found"), None);
+    }
+    let fix = match std::env::consts::OS {
+        "macos" => library.brew.to_owned(),
+        "windows" => t!("doctor_fix_library_dir", var = library.dir_var).into(),
+        _ => library.apt.to_owned(),
+    };
+    Check::new(
+        library.name,
+        Status::Error,
+        t!("doctor_library_missing"),
+        Some(fix),
+    )
+}
+
+fn library_dirs(library: &Library) -> Vec<PathBuf> {
+    std::env::var_os(library.dir_var)
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(LIBRARY_DIRS.iter().map(PathBuf::from))
+        .collect()
+}
+
+/// Runs every check that applies to the project in `project_path`, or only the general ones
+/// outside a project.
+fn checks(project_path: &Path, probe: Probe) -> Vec<Check> {
+    let rust_version = manifest::rust_version(project_path)
+        .ok()
+        .flatten()
+        .or_else(classic::rust_version)
+        .and_then(|version| parse_version(&version))
+        .unwrap_or(Version::new(1, 0, 0));
+    let mut checks = vec![
+        check_toolchain(probe, "rustc", &rust_version),
+        check_toolchain(probe, "cargo", &rust_version),
+        check_program(probe, "git", "git", "https://git-scm.com/downloads".into()),
+    ];
+    let Ok(metadata) = manifest::read(project_path) else {
+        return checks;
+    };
+    if let Some((name, program, fix)) = db_cli(metadata.db_lib, metadata.db_type) {
+        checks.push(check_program(probe, name, program, fix));
+    }
+    if metadata.db_lib == DbLib::Diesel {
+        let library = match metadata.db_type {
+            DbType::Postgres => Some(&LIBPQ),
+            DbType::Mysql => Some(&LIBMYSQLCLIENT),
+            _ => None,
+        };
+        if let Some(library) = library {
+            checks.push(check_library(probe, library, &library_dirs(library)));
+        }
+    }
+    checks
+}
+
+/// The checks as a Markdown report.
+fn report(checks: &[Check]) -> String {
+    let mut report = String::from("# salvo doctor\n\n");
+    for check in checks {
+        let _ = writeln!(
+            report,
+            "- {} {}: {}",
+            check.symbol(),
+            check.name,
+            check.detail
+        );
+        if let Some(fix) = &check.fix {
+            let _ = writeln!(report, "  - `{fix}`");
+        }
+    }
+    report
+}
+
+/// Checks the tools the project in `project_path` needs, writing a Markdown report to `output`.
+pub fn run(project_path: &Path, output: Option<&Path>) -> Result<()> {
+    let in_project = manifest::read(project_path).is_ok();
+    if !in_project {
+        gray(t!(
+            "doctor_not_a_project",
+            path = project_path.to_string_lossy()
+        ));
+    }
+    let checks = checks(project_path, &run_probe);
+    for check in &checks {
+        printer::event("check", json!(check));
+        if printer::is_json() {
+            continue;
+        }
+        let line = format!("{} {}: {}", check.symbol(), check.name, check.detail);
+        match check.status {
+            Status::Ok => success(line),
+            Status::Warning => warning(line),
+            Status::Error => printer::error(line),
+        }
+        if let Some(fix) = &check.fix {
+            gray(t!("doctor_fix", fix = fix));
+        }
+    }
+    if let Some(output) = output {
+        if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+            utils::create_dir_all(parent)?;
+        }
+        fs::write(output, report(&checks))?;
+        success(t!("doctor_report_written", path = output.to_string_lossy()));
+    }
+
+    let errors = checks.iter().filter(|c| c.status == Status::Error).count();
+    if errors > 0 {
+        anyhow::bail!(t!("doctor_failed", count = errors));
+    }
+    if checks.iter().all(|c| c.status == Status::Ok) {
+        success(t!("doctor_all_good"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use semver::Version;
+
+    use super::{LIBPQ, Status, check_library, check_toolchain, checks, parse_version, report};
+    use crate::utils::unique_temp_dir;
+
+    #[test]
+    fn test_parse_version_reads_tool_output() {
+        let version = |output| parse_version(output);
+        assert_eq!(
+            version("rustc 1.94.0 (4a4ef493e 2026-03-02)"),
+            Some(Version::new(1, 94, 0))
+        );
+        assert_eq!(
+            version("cargo 1.96.0-nightly (a1b2c3 2026-05-01)"),
+            Some(Version::new(1, 96, 0))
+        );
+        assert_eq!(
+            version("git version 2.43.0.windows.1"),
+            Some(Version::new(2, 43, 0))
+        );
+        assert_eq!(version("sqlx-cli 0.8.6"), Some(Version::new(0, 8, 6)));
+        assert_eq!(version("1.94"), Some(Version::new(1, 94, 0)));
+        assert_eq!(version("mongosh"), None);
+    }
+
+    #[test]
+    fn test_toolchain_older_than_the_template_fails() {
+        let required = Version::new(1, 94, 0);
+        let old = |_: &str, _: &[&str]| Some("rustc 1.89.0 (29483883e 2025-08-04)".to_owned());
+        let check = check_toolchain(&old, "rustc", &required);
+        assert_eq!(check.status, Status::Error);
+        assert_eq!(check.fix.as_deref(), Some("rustup update stable"));
+
+        let new = |_: &str, _: &[&str]| Some("rustc 1.94.1 (e408947bf 2026-03-25)".to_owned());
+        assert_eq!(check_toolchain(&new, "rustc", &required).status, Status::Ok);
+        let missing = |_: &str, _: &[&str]| None;
+        assert_eq!(
+            check_toolchain(&missing, "rustc", &required).status,
+            Status::Error
+        );
+    }
+
+    #[test]
+    fn test_checks_follow_the_db_lib_of_the_project() {
+        let dir = unique_temp_dir("doctor");
+        fs::create_dir_all(&dir).unwrap();
+        // Only the toolchain is installed, an old one.
+        let probe = |program: &str, _: &[&str]| match program {
+            "rustc" => Some("rustc 1.80.0 (051478957 2024-07-21)".to_owned()),
+            "cargo" => Some("cargo 1.94.0 (85eff7c80 2026-01-15)".to_owned()),
+            _ => None,
+        };
+
+        let names = |checks: &[super::Check]| {
+            checks
+                .iter()
+                .map(|check| (check.name.clone(), check.status))
+                .collect::<Vec<_>>()
+        };
+        let outside = checks(&dir, &probe);
+        assert_eq!(
+            names(&outside),
+            [
+                ("rustc".to_owned(), Status::Error),
+                ("cargo".to_owned(), Status::Ok),
+                ("git".to_owned(), Status::Warning),
+            ]
+        );
+
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nrust-version = \"1.95\"\n\n[package.metadata.salvo]\ndb_lib = \"diesel\"\ndb_type = \"postgres\"\n",
+        )
+        .unwrap();
+        let diesel = checks(&dir, &probe);
+        assert_eq!(diesel[1].status, Status::Error, "the project needs 1.95");
+        assert_eq!(diesel[3].name, "diesel_cli");
+        assert!(
+            diesel[3]
+                .fix
+                .as_deref()
+                .unwrap()
+                .contains("--features postgres")
+        );
+        assert_eq!(diesel[4].name, "libpq");
+
+        let report = report(&diesel);
+        assert!(report.starts_with("# salvo doctor\n"));
+        assert!(report.contains("- ✘ rustc: "));
+        assert!(report.contains("  - `rustup update stable`"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_library_is_found_by_pkg_config_or_in_a_lib_dir() {
+        let dir = unique_temp_dir("doctor");
+        fs::create_dir_all(&dir).unwrap();
+        let none = |_: &str, _: &[&str]| None;
+        let dirs = [dir.clone()];
+        assert_eq!(check_library(&none, &LIBPQ, &dirs).status, Status::Error);
+        let pkg_config = |program: &str, args: &[&str]| {
+            (program == "pkg-config" && args.contains(&"libpq")).then(|| "-lpq".to_owned())
+        };
+        assert_eq!(check_library(&pkg_config, &LIBPQ, &dirs).status, Status::Ok);
+        fs::write(dir.join("libpq.so"), "").unwrap();
+        assert_eq!(check_library(&none, &LIBPQ, &dirs).status, Status::Ok);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
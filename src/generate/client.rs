@@ -0,0 +1,469 @@
+use std::collections::HashSet;
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use liquid::Parser;
+use rust_i18n::t;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::openapi::{self, METHODS, Types};
+use crate::manifest;
+use crate::printer::{generated_file, gray, success, warning};
+use crate::utils;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "./templates/generate/client"]
+struct Template;
+
+/// First line of the files that are rewritten on every run.
+const GENERATED_HEADER: &str = "// Generated by `salvo generate client`";
+/// Methods of the generated `Client` that operations must not shadow.
+const RESERVED: [&str; 9] = [
+    "new",
+    "with_http_client",
+    "base_url",
+    "set_token",
+    "clear_token",
+    "token",
+    "request",
+    "send",
+    "send_empty",
+];
+/// Types that `lib.rs` defines or imports, models are renamed rather than shadowed by them.
+const RESERVED_TYPES: [&str; 9] = [
+    "Arc",
+    "Client",
+    "DeserializeOwned",
+    "Error",
+    "Method",
+    "RequestBuilder",
+    "Result",
+    "RwLock",
+    "StatusCode",
+];
+/// How long to wait for the project to build and start serving its OpenAPI document.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Generates a typed `reqwest` client crate for the API of a project.
+///
+/// The OpenAPI document is read from `from`, a file or an http(s) URL, or else
+/// exported by the project itself.
+pub async fn generate(project_path: &Path, from: Option<&str>, out: Option<&Path>) -> Result<()> {
+    manifest::read(project_path)?;
+    let package = manifest::package_name(project_path)?;
+    let (doc, spec_name) = match from {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            (fetch(url, false).await?, url.to_owned())
+        }
+        Some(path) => (
+            openapi::load(Path::new(path))?,
+            Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        ),
+        None => (
+            fetch_from_project(project_path).await?,
+            "/api-doc/openapi.json".to_owned(),
+        ),
+    };
+
+    let mut types = Types::new(&doc);
+    types.reserve(&RESERVED_TYPES);
+    types.define_components();
+    let operations = collect_methods(&doc, &mut types);
+    if operations.is_empty() {
+        anyhow::bail!(t!("error_openapi_no_operations", path = spec_name));
+    }
+
+    let out = out.map_or_else(|| project_path.join("client"), Path::to_path_buf);
+    let src = out.join("src");
+    let lib_path = src.join("lib.rs");
+    let models_path = src.join("models.rs");
+    for path in [&lib_path, &models_path] {
+        if path.exists() && !fs::read_to_string(path)?.starts_with(GENERATED_HEADER) {
+            anyhow::bail!(t!(
+                "error_not_generated_file",
+                path = path.to_string_lossy()
+            ));
+        }
+    }
+    let parser = liquid::ParserBuilder::with_stdlib().build()?;
+    let title = doc
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or(&package);
+    let globals = liquid::object!({
+        "crate_name": format!("{package}-client"),
+        "title": title,
+        "spec": spec_name,
+        "methods": liquid::model::to_value(&operations)?,
+    });
+    utils::create_dir_all(&src)?;
+    // The manifest and ignore file are the user's to change once they exist.
+    for (name, path) in [
+        ("Cargo.toml", out.join("Cargo.toml")),
+        (".gitignore", out.join(".gitignore")),
+    ] {
+        if !path.exists() {
+            write(&path, &render(&parser, name, &globals)?)?;
+        }
+    }
+    write(&lib_path, &render(&parser, "lib.rs", &globals)?)?;
+    write(
+        &models_path,
+        &openapi::render_models(&parser, &types, &spec_name, false)?,
+    )?;
+    success(t!(
+        "generate_client_done",
+        count = operations.len(),
+        path = out.to_string_lossy()
+    ));
+    Ok(())
+}
+
+fn render(parser: &Parser, name: &str, globals: &liquid::Object) -> Result<String> {
+    let file = Template::get(&format!("{name}.liquid")).expect("file must exist");
+    let template = parser.parse(&String::from_utf8_lossy(&file.data))?;
+    Ok(template.render(globals)?)
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)?;
+    generated_file(path);
+    Ok(())
+}
+
+/// Downloads an OpenAPI document, accepting the self-signed certificate of a local project.
+async fn fetch(url: &str, local: bool) -> Result<Value> {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(local)
+        .build()?;
+    let content = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| anyhow::anyhow!(t!("error_openapi_invalid", path = url, error = e)))?
+        .text()
+        .await?;
+    openapi::parse(&content, url, content.trim_start().starts_with('{'))
+}
+
+/// Reads the OpenAPI document of the project, through `--export-openapi` when its `main.rs` supports it.
+async fn fetch_from_project(project_path: &Path) -> Result<Value> {
+    let main = fs::read_to_string(project_path.join("src/main.rs")).unwrap_or_default();
+    if main.contains("--export-openapi") {
+        return export_from_project(project_path);
+    }
+    serve_from_project(project_path).await
+}
+
+fn export_from_project(project_path: &Path) -> Result<Value> {
+    let spec_path = std::env::temp_dir().join(format!("salvo-openapi-{}.json", std::process::id()));
+    gray(t!("client_exporting_project"));
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--", "--export-openapi"])
+        .arg(&spec_path)
+        .current_dir(project_path)
+        .stdout(Stdio::null())
+        .status()?;
+    if !status.success() {
+        anyhow::bail!(t!("error_client_project_exited", status = status));
+    }
+    let doc = openapi::load(&spec_path);
+    fs::remove_file(&spec_path).ok();
+    doc
+}
+
+/// Builds and runs the project on a free port until it serves its OpenAPI document.
+async fn serve_from_project(project_path: &Path) -> Result<Value> {
+    let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    let scheme = if uses_tls(project_path) {
+        "https"
+    } else {
+        "http"
+    };
+    let url = format!("{scheme}://127.0.0.1:{port}/api-doc/openapi.json");
+    gray(t!("client_starting_project", url = url));
+    let mut child = ChildGuard(
+        Command::new("cargo")
+            .args(["run", "--quiet"])
+            .current_dir(project_path)
+            .env("APP_LISTEN_ADDR", format!("127.0.0.1:{port}"))
+            .stdout(Stdio::null())
+            .spawn()?,
+    );
+    let started = Instant::now();
+    loop {
+        if let Ok(doc) = fetch(&url, true).await {
+            return Ok(doc);
+        }
+        if let Some(status) = child.0.try_wait()? {
+            anyhow::bail!(t!("error_client_project_exited", status = status));
+        }
+        if started.elapsed() > STARTUP_TIMEOUT {
+            anyhow::bail!(t!("error_client_project_timeout", url = url));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Stops the project when the document has been fetched or fetching failed.
+struct ChildGuard(Child);
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Whether the project's `config.toml` enables a `[tls]` table.
+fn uses_tls(project_path: &Path) -> bool {
+    fs::read_to_string(project_path.join("config.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .is_some_and(|config| config.contains_key("tls"))
+}
+
+#[derive(Debug, Serialize)]
+struct Method {
+    name: String,
+    http_method: String,
+    doc: Vec<String>,
+    args: Vec<String>,
+    /// The arguments go on their own lines when the signature is longer than rustfmt allows.
+    wrap_args: bool,
+    /// The path as a string literal, or a `format!` call filling in the path parameters.
+    path: String,
+    wrap_request: bool,
+    /// Statements adding query parameters, headers and the body to `request`.
+    statements: Vec<String>,
+    return_type: String,
+    /// Login operations keep the returned token for the requests that follow.
+    stores_token: bool,
+}
+
+fn collect_methods(doc: &Value, types: &mut Types) -> Vec<Method> {
+    let Some(paths) = doc.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut names: HashSet<String> = RESERVED.iter().map(|name| (*name).to_owned()).collect();
+    let mut methods = Vec::new();
+    for (path, item) in paths {
+        let item = types.resolve(item);
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            // Salvo prefixes operation ids with the module path, such as `app.routers.user.list_users`.
+            let base = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(|id| openapi::plain_ident(id.rsplit('.').next().unwrap_or(id)))
+                .unwrap_or_else(|| openapi::plain_ident(&format!("{method} {path}")));
+            let name = (1..)
+                .map(|index| match index {
+                    1 => base.clone(),
+                    _ => format!("{base}_{index}"),
+                })
+                .find(|candidate| names.insert(candidate.clone()))
+                .expect("an unused method name");
+            methods.push(build_method(types, path, item, method, operation, name));
+        }
+    }
+    methods
+}
+
+fn build_method(
+    types: &mut Types,
+    path: &str,
+    item: &Value,
+    method: &str,
+    operation: &Value,
+    name: String,
+) -> Method {
+    let type_prefix = openapi::type_name(&name);
+    let mut args = Vec::new();
+    let mut idents = HashSet::new();
+    let mut path_format = path.to_owned();
+    let mut path_args = Vec::new();
+    let mut statements = Vec::new();
+    for parameter in openapi::parameters(types, item, operation) {
+        let param_name = parameter.get("name").and_then(Value::as_str).unwrap_or("");
+        let location = parameter.get("in").and_then(Value::as_str).unwrap_or("");
+        let required = parameter.get("required").and_then(Value::as_bool) == Some(true);
+        let schema = parameter.get("schema").cloned().unwrap_or(Value::Null);
+        let hint = format!("{type_prefix}{}", openapi::type_name(param_name));
+        let rust_type = types.rust_type(&schema, &hint);
+        let rust_type = borrowed(openapi::strip_option(&rust_type));
+        let mut ident = openapi::plain_ident(param_name);
+        if !idents.insert(ident.clone()) {
+            ident = format!("{ident}_{location}");
+            idents.insert(ident.clone());
+        }
+        match location {
+            "path" => {
+                path_format = path_format.replace(&format!("{{{param_name}}}"), "{}");
+                path_args.push(format!("segment({ident})"));
+                args.push(format!("{ident}: {rust_type}"));
+            }
+            "query" | "header" => {
+                let add = |value: &str| {
+                    if location == "query" {
+                        format!("request = request.query(&[({param_name:?}, {value})]);")
+                    } else {
+                        format!("request = request.header({param_name:?}, {value}.to_string());")
+                    }
+                };
+                let is_list = rust_type.starts_with("&[");
+                let add_value = add("value");
+                statements.push(match (required, is_list) {
+                    (true, false) => add(&ident),
+                    (true, true) => {
+                        format!("for value in {ident} {{\n            {add_value}\n        }}")
+                    }
+                    (false, false) => format!(
+                        "if let Some(value) = {ident} {{\n            {add_value}\n        }}"
+                    ),
+                    (false, true) => format!(
+                        "for value in {ident}.unwrap_or_default() {{\n            {add_value}\n        }}"
+                    ),
+                });
+                args.push(if required {
+                    format!("{ident}: {rust_type}")
+                } else {
+                    format!("{ident}: Option<{rust_type}>")
+                });
+            }
+            _ => warning(t!(
+                "warning_openapi_parameter_skipped",
+                name = param_name,
+                location = location,
+                operation = name
+            )),
+        }
+    }
+    if let Some(body) = operation.get("requestBody") {
+        let content = types
+            .resolve(body)
+            .get("content")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let json = content.iter().find(|(media, _)| media.ends_with("json"));
+        let form = content.get_key_value("application/x-www-form-urlencoded");
+        match json.or(form) {
+            Some((media, value)) => {
+                let schema = value.get("schema").cloned().unwrap_or(Value::Null);
+                let rust_type = types.rust_type(&schema, &format!("{type_prefix}Body"));
+                args.push(format!("body: &{rust_type}"));
+                statements.push(if media.ends_with("json") {
+                    "request = request.json(body);".to_owned()
+                } else {
+                    "request = request.form(body);".to_owned()
+                });
+            }
+            None => warning(t!(
+                "warning_openapi_parameter_skipped",
+                name = "requestBody",
+                location = "body",
+                operation = name
+            )),
+        }
+    }
+    let return_type = openapi::success_schema(types, operation)
+        .map(|schema| types.rust_type(&schema, &format!("{type_prefix}Response")))
+        .unwrap_or_else(|| "()".to_owned());
+    let stores_token = method == "post"
+        && path.trim_end_matches('/').ends_with("/login")
+        && types.items.iter().any(|item| {
+            item.name == return_type
+                && item.fields.iter().any(|field| {
+                    field.name == "token" && !field.optional && field.rust_type == "String"
+                })
+        });
+    let path = if path_args.is_empty() {
+        format!("{path:?}")
+    } else {
+        format!("&format!({path_format:?}, {})", path_args.join(", "))
+    };
+    let signature = format!(
+        "    pub async fn {name}(&self, {}) -> Result<{return_type}> {{",
+        args.join(", ")
+    );
+    let request = format!(
+        "        let mut request = self.request(Method::{}, {path});",
+        method.to_uppercase()
+    );
+    Method {
+        wrap_args: signature.len() > 100,
+        wrap_request: request.len() - if statements.is_empty() { 4 } else { 0 } > 100,
+        name,
+        http_method: method.to_uppercase(),
+        doc: openapi::doc_lines(operation),
+        args,
+        path,
+        statements,
+        return_type,
+        stores_token,
+    }
+}
+
+/// Arguments borrow strings and lists rather than taking them by value.
+fn borrowed(rust_type: &str) -> String {
+    match rust_type {
+        "String" => "&str".to_owned(),
+        _ => match rust_type
+            .strip_prefix("Vec<")
+            .and_then(|inner| inner.strip_suffix('>'))
+        {
+            Some(inner) => format!("&[{inner}]"),
+            None => rust_type.to_owned(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{RESERVED_TYPES, Types, borrowed};
+
+    #[test]
+    fn test_models_do_not_shadow_client_types() {
+        let doc = json!({
+            "components": {
+                "schemas": {
+                    "app.models.Error": {"type": "object", "properties": {"code": {"type": "integer"}}},
+                    "app.routers.user.User": {"type": "object", "properties": {"id": {"type": "string"}}}
+                }
+            }
+        });
+        let mut types = Types::new(&doc);
+        types.reserve(&RESERVED_TYPES);
+        types.define_components();
+        let names: Vec<&str> = types.items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Error2", "User"]);
+        assert_eq!(
+            types.rust_type(
+                &json!({"$ref": "#/components/schemas/app.models.Error"}),
+                "X"
+            ),
+            "Error2"
+        );
+    }
+
+    #[test]
+    fn test_arguments_borrow_strings_and_lists() {
+        assert_eq!(borrowed("String"), "&str");
+        assert_eq!(borrowed("Vec<Pet>"), "&[Pet]");
+        assert_eq!(borrowed("i64"), "i64");
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use rust_i18n::t;
+
+use crate::printer::{generated_file, warning};
+use crate::utils;
+
+pub mod client;
+pub(crate) mod introspect;
+pub mod openapi;
+pub mod schema;
+
+/// Writes a generated file, leaving an existing one untouched unless `force` is set.
+fn write_file(path: &Path, contents: &str, force: bool) -> Result<bool> {
+    if path.exists() && !force {
+        warning(t!(
+            "warning_file_exists_skipped",
+            path = path.to_string_lossy()
+        ));
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        utils::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    generated_file(path);
+    Ok(true)
+}
+
+/// Inserts `line` after the last declaration starting with `after`, or at the top of the file.
+///
+/// Nothing is written when the file already contains the line.
+fn insert_line(path: &Path, after: &str, line: &str) -> Result<()> {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    if content
+        .lines()
+        .any(|existing| existing.trim() == line.trim())
+    {
+        return Ok(());
+    }
+    let mut lines: Vec<&str> = content.lines().collect();
+    match lines
+        .iter()
+        .rposition(|existing| existing.starts_with(after) && existing.ends_with(';'))
+    {
+        Some(index) => lines.insert(index + 1, line),
+        None if lines.is_empty() => lines.push(line),
+        None => lines.splice(0..0, [line, ""]).for_each(drop),
+    }
+    let mut updated = lines.join("\n");
+    if content.is_empty() || content.ends_with('\n') {
+        updated.push('\n');
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Declares the router module and pushes its routes onto the router built on the `parent` line.
+fn mount_router(routers_path: &Path, module: &str, parent: &str) -> Result<()> {
+    insert_line(routers_path, "mod ", &format!("mod {module};"))?;
+    let content = fs::read_to_string(routers_path)?;
+    let push = format!(".push({module}::router())");
+    if content.contains(&push) {
+        return Ok(());
+    }
+    let mut lines: Vec<String> = content.lines().map(str::to_owned).collect();
+    if let Some(index) = lines.iter().position(|line| line.contains(parent)) {
+        let indent = lines[index].len() - lines[index].trim_start().len();
+        // Keep hoops first and previously generated routers in the order they were added.
+        let mounted = lines[index + 1..]
+            .iter()
+            .map(|line| line.trim_start())
+            .take_while(|line| {
+                line.starts_with(".hoop(")
+                    || (line.starts_with(".push(") && line.ends_with("::router())"))
+            })
+            .count();
+        lines.insert(
+            index + 1 + mounted,
+            format!("{}{push}", " ".repeat(indent + 4)),
+        );
+        fs::write(routers_path, lines.join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::insert_line;
+    use crate::utils::unique_temp_dir;
+
+    #[test]
+    fn insert_line_keeps_declarations_together() {
+        let path = unique_temp_dir("insert").with_extension("rs");
+        fs::write(
+            &path,
+            "mod auth;\nmod user;\n\nuse crate::hoops;\n\nmod tests {\n}\n",
+        )
+        .unwrap();
+
+        insert_line(&path, "mod ", "mod posts;").unwrap();
+        insert_line(&path, "mod ", "mod posts;").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "mod auth;\nmod user;\nmod posts;\n\nuse crate::hoops;\n\nmod tests {\n}\n"
+        );
+
+        insert_line(&path, "pub mod ", "pub mod tags;").unwrap();
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .starts_with("pub mod tags;\n\nmod auth;")
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,1002 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use liquid::Parser;
+use rust_i18n::t;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{insert_line, mount_router};
+use crate::manifest;
+use crate::namer::{is_keyword, to_pascal_case, to_rust_ident, to_snake_case};
+use crate::printer::{generated_file, success, warning};
+use crate::utils;
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "./templates/generate/openapi"]
+struct Template;
+
+/// First line of the schema module, which is rewritten on every run.
+const MODELS_HEADER: &str = "// Generated by `salvo generate openapi`";
+/// Router files keep everything outside of these markers, so handler bodies survive a re-run.
+const ROUTES_BEGIN: &str = "// salvo-cli: generated routes begin";
+const ROUTES_END: &str = "// salvo-cli: generated routes end";
+pub(super) const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Generates schemas, handler stubs and router wiring from an OpenAPI 3 document.
+pub fn generate(project_path: &Path, spec_path: &Path) -> Result<()> {
+    manifest::read(project_path)?;
+    let doc = load(spec_path)?;
+    let mut types = Types::new(&doc);
+    types.define_components();
+    let operations = collect_operations(&doc, &mut types);
+    if operations.is_empty() {
+        anyhow::bail!(t!(
+            "error_openapi_no_operations",
+            path = spec_path.to_string_lossy()
+        ));
+    }
+
+    let src = project_path.join("src");
+    let parser = liquid::ParserBuilder::with_stdlib().build()?;
+    let models_path = src.join("models/openapi.rs");
+    if models_path.exists() && !fs::read_to_string(&models_path)?.starts_with(MODELS_HEADER) {
+        anyhow::bail!(t!(
+            "error_not_generated_file",
+            path = models_path.to_string_lossy()
+        ));
+    }
+    let spec_name = spec_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let models = render_models(&parser, &types, &spec_name, true)?;
+    utils::create_dir_all(src.join("models"))?;
+    fs::write(&models_path, models)?;
+    generated_file(&models_path);
+    insert_line(&src.join("models/mod.rs"), "pub mod ", "pub mod openapi;")?;
+    if types.uses_json_value {
+        add_serde_json(&project_path.join("Cargo.toml"))?;
+    }
+
+    let mut modules: BTreeMap<String, Vec<Operation>> = BTreeMap::new();
+    for operation in operations {
+        modules
+            .entry(operation.module.clone())
+            .or_default()
+            .push(operation);
+    }
+    let mut stubs = 0;
+    for (module, operations) in &modules {
+        let path = src.join(format!("routers/{module}.rs"));
+        let existing = fs::read_to_string(&path).ok();
+        if existing
+            .as_deref()
+            .is_some_and(|content| !content.contains(ROUTES_BEGIN))
+        {
+            warning(t!(
+                "warning_router_module_conflict",
+                path = path.to_string_lossy()
+            ));
+            continue;
+        }
+        let routes = render(&parser, "routes.rs", routes_object(operations, &types))?;
+        let mut content = match &existing {
+            Some(content) => replace_routes(content, &routes),
+            None => routes,
+        };
+        for operation in operations {
+            if !content.contains(&format!("fn {}(", operation.handler)) {
+                content.push_str(&render(
+                    &parser,
+                    "handler.rs",
+                    liquid::to_object(operation)?,
+                )?);
+                stubs += 1;
+            }
+        }
+        fs::write(&path, content)?;
+        generated_file(&path);
+        if existing.is_none() {
+            mount_router(
+                &src.join("routers/mod.rs"),
+                module,
+                "let router = Router::new()",
+            )?;
+        }
+    }
+    success(t!(
+        "generate_openapi_done",
+        count = modules.values().map(Vec::len).sum::<usize>(),
+        stubs = stubs
+    ));
+    Ok(())
+}
+
+pub(super) fn load(spec_path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(spec_path).map_err(|e| {
+        anyhow::anyhow!(t!(
+            "error_openapi_invalid",
+            path = spec_path.to_string_lossy(),
+            error = e
+        ))
+    })?;
+    let json = spec_path.extension().is_some_and(|ext| ext == "json");
+    parse(&content, &spec_path.to_string_lossy(), json)
+}
+
+/// Parses an OpenAPI 3 document, `source` names it in error messages.
+pub(super) fn parse(content: &str, source: &str, json: bool) -> Result<Value> {
+    let parsed = if json {
+        serde_json::from_str::<Value>(content).map_err(anyhow::Error::from)
+    } else {
+        serde_yaml::from_str::<Value>(content).map_err(anyhow::Error::from)
+    };
+    let doc = parsed
+        .map_err(|e| anyhow::anyhow!(t!("error_openapi_invalid", path = source, error = e)))?;
+    let version = doc.get("openapi").and_then(Value::as_str).unwrap_or("");
+    if !version.starts_with("3.") {
+        anyhow::bail!(t!(
+            "error_openapi_unsupported_version",
+            version = if version.is_empty() { "2.0" } else { version }
+        ));
+    }
+    Ok(doc)
+}
+
+fn render(parser: &Parser, name: &str, globals: liquid::Object) -> Result<String> {
+    let file = Template::get(&format!("{name}.liquid")).expect("file must exist");
+    let template = parser.parse(&String::from_utf8_lossy(&file.data))?;
+    Ok(template.render(&globals)?)
+}
+
+/// Renders the defined types, with salvo schemas for a server or as plain serde types for a client.
+pub(super) fn render_models(
+    parser: &Parser,
+    types: &Types,
+    spec_name: &str,
+    server: bool,
+) -> Result<String> {
+    let has_schemas = types
+        .items
+        .iter()
+        .any(|item| matches!(item.kind, "struct" | "enum"));
+    let has_params = types.items.iter().any(|item| item.kind == "params");
+    let oapi_imports: Vec<&str> = [(has_params, "ToParameters"), (has_schemas, "ToSchema")]
+        .into_iter()
+        .filter_map(|(used, name)| used.then_some(name))
+        .collect();
+    let serde_imports: Vec<&str> = [
+        (has_params || has_schemas, "Deserialize"),
+        (has_schemas, "Serialize"),
+    ]
+    .into_iter()
+    .filter_map(|(used, name)| used.then_some(name))
+    .collect();
+    render(
+        parser,
+        "models.rs",
+        liquid::object!({
+            "command": if server { "openapi" } else { "client" },
+            "server": server,
+            "spec": spec_name,
+            "items": liquid::model::to_value(&types.items)?,
+            "oapi_imports": if server { oapi_imports } else { Vec::new() },
+            "serde_imports": serde_imports,
+        }),
+    )
+}
+
+/// Swaps the generated block of an existing router file, keeping everything else as it is.
+fn replace_routes(content: &str, routes: &str) -> String {
+    let (Some(begin), Some(end)) = (content.find(ROUTES_BEGIN), content.find(ROUTES_END)) else {
+        return format!("{routes}{content}");
+    };
+    let end = content[end..]
+        .find('\n')
+        .map_or(content.len(), |offset| end + offset + 1);
+    format!("{}{routes}{}", &content[..begin], &content[end..])
+}
+
+/// Generated types may fall back to `serde_json::Value`, which needs the crate in the project.
+fn add_serde_json(manifest_path: &Path) -> Result<()> {
+    let manifest = fs::read_to_string(manifest_path)?;
+    if !manifest.lines().any(|line| line.starts_with("serde_json")) {
+        insert_line(manifest_path, "serde = ", r#"serde_json = "1""#)?;
+    }
+    Ok(())
+}
+
+/// A snake case name that works as a plain identifier, which extractors use as the parameter name.
+pub(super) fn plain_ident(name: &str) -> String {
+    let ident = to_snake_case(name);
+    if ident.is_empty() {
+        "param".to_owned()
+    } else if is_keyword(&ident) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+pub(super) fn type_name(name: &str) -> String {
+    let name = to_pascal_case(name);
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("T{name}")
+    } else {
+        name
+    }
+}
+
+pub(super) fn doc_lines(schema: &Value) -> Vec<String> {
+    let mut lines = Vec::new();
+    for key in ["summary", "description"] {
+        if let Some(text) = schema.get(key).and_then(Value::as_str) {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(text.trim().lines().map(|line| line.trim_end().to_owned()));
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TypeItem {
+    /// `struct`, `enum`, `alias` or `params`.
+    pub(super) kind: &'static str,
+    pub(super) name: String,
+    schema_name: String,
+    doc: Vec<String>,
+    pub(super) fields: Vec<Field>,
+    variants: Vec<Variant>,
+    target: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Field {
+    pub(super) name: String,
+    ident: String,
+    pub(super) rust_type: String,
+    renamed: bool,
+    pub(super) optional: bool,
+    doc: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Variant {
+    name: String,
+    value: String,
+}
+
+/// Turns JSON schemas into Rust types, collecting the named types that need a definition.
+pub(super) struct Types<'a> {
+    doc: &'a Value,
+    pub(super) items: Vec<TypeItem>,
+    names: HashSet<String>,
+    /// Rust names of the component schemas, keyed by schema name.
+    components: HashMap<String, String>,
+    pub(super) uses_json_value: bool,
+}
+
+impl<'a> Types<'a> {
+    pub(super) fn new(doc: &'a Value) -> Self {
+        Self {
+            doc,
+            items: Vec::new(),
+            names: HashSet::new(),
+            components: HashMap::new(),
+            uses_json_value: false,
+        }
+    }
+
+    /// Keeps names used by the code around the generated types from being defined again.
+    pub(super) fn reserve(&mut self, names: &[&str]) {
+        self.names
+            .extend(names.iter().map(|name| (*name).to_owned()));
+    }
+
+    /// Follows local `$ref`s such as `#/components/parameters/Limit`.
+    pub(super) fn resolve<'v>(&self, mut value: &'v Value) -> &'v Value
+    where
+        'a: 'v,
+    {
+        for _ in 0..16 {
+            let Some(target) = value
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.doc.pointer(pointer))
+            else {
+                break;
+            };
+            value = target;
+        }
+        value
+    }
+
+    fn unique_name(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut index = 2;
+        while !self.names.insert(unique.clone()) {
+            unique = format!("{name}{index}");
+            index += 1;
+        }
+        unique
+    }
+
+    pub(super) fn define_components(&mut self) {
+        let Some(schemas) = self
+            .doc
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+        else {
+            return;
+        };
+        // Salvo names schemas by their full module path, such as `app.models.user.User`.
+        let short_name = |name: &str| type_name(name.rsplit('.').next().unwrap_or(name));
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for name in schemas.keys() {
+            *counts.entry(short_name(name)).or_default() += 1;
+        }
+        // Reserve every component name first, so inline types never take one of them.
+        for name in schemas.keys() {
+            let short = short_name(name);
+            let rust_name = self.unique_name(if counts[&short] == 1 {
+                short
+            } else {
+                type_name(name)
+            });
+            self.components.insert(name.clone(), rust_name);
+        }
+        for (name, schema) in schemas {
+            let rust_name = self.components[name].clone();
+            let enum_values = schema.get("enum").and_then(Value::as_array);
+            if is_object(schema) {
+                self.define_struct(rust_name, name, schema);
+            } else if let Some(values) = enum_values.filter(|_| schema_type(schema).0 == "string") {
+                let mut seen = HashSet::new();
+                let variants = values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter_map(|value| {
+                        let variant = type_name(value);
+                        seen.insert(variant.clone()).then(|| Variant {
+                            name: variant,
+                            value: value.to_owned(),
+                        })
+                    })
+                    .collect();
+                self.items.push(TypeItem {
+                    kind: "enum",
+                    name: rust_name,
+                    schema_name: name.clone(),
+                    doc: doc_lines(schema),
+                    fields: Vec::new(),
+                    variants,
+                    target: String::new(),
+                });
+            } else {
+                let target = self.rust_type(schema, &rust_name);
+                self.items.push(TypeItem {
+                    kind: "alias",
+                    name: rust_name,
+                    schema_name: name.clone(),
+                    doc: doc_lines(schema),
+                    fields: Vec::new(),
+                    variants: Vec::new(),
+                    target,
+                });
+            }
+        }
+    }
+
+    fn define_struct(&mut self, name: String, schema_name: &str, schema: &Value) {
+        let mut properties = Vec::new();
+        let mut required = HashSet::new();
+        self.collect_properties(schema, &mut properties, &mut required);
+        let fields = properties
+            .into_iter()
+            .map(|(property, property_schema)| {
+                let ident = to_rust_ident(&to_snake_case(&property));
+                let hint = format!("{name}{}", type_name(&property));
+                let rust_type = self.rust_type(&property_schema, &hint);
+                let optional = !required.contains(&property);
+                Field {
+                    renamed: ident != property,
+                    rust_type: if optional && !rust_type.starts_with("Option<") {
+                        format!("Option<{rust_type}>")
+                    } else {
+                        rust_type
+                    },
+                    doc: doc_lines(&property_schema),
+                    name: property,
+                    ident,
+                    optional,
+                }
+            })
+            .collect();
+        self.items.push(TypeItem {
+            kind: "struct",
+            name,
+            schema_name: schema_name.to_owned(),
+            doc: doc_lines(schema),
+            fields,
+            variants: Vec::new(),
+            target: String::new(),
+        });
+    }
+
+    /// Gathers the properties of a schema, merging the parts of an `allOf`.
+    fn collect_properties(
+        &self,
+        schema: &Value,
+        properties: &mut Vec<(String, Value)>,
+        required: &mut HashSet<String>,
+    ) {
+        let schema = self.resolve(schema);
+        for part in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.collect_properties(part, properties, required);
+        }
+        if let Some(map) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in map {
+                if !properties.iter().any(|(existing, _)| existing == name) {
+                    properties.push((name.clone(), property.clone()));
+                }
+            }
+        }
+        required.extend(
+            schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_owned),
+        );
+    }
+
+    /// The Rust type of a schema, `hint` names the struct generated for an inline object.
+    pub(super) fn rust_type(&mut self, schema: &Value, hint: &str) -> String {
+        // A composition of a single schema, possibly with `null`, is that schema.
+        for key in ["allOf", "oneOf", "anyOf"] {
+            let Some(parts) = schema.get(key).and_then(Value::as_array) else {
+                continue;
+            };
+            let non_null: Vec<&Value> = parts
+                .iter()
+                .filter(|part| part.get("type").and_then(Value::as_str) != Some("null"))
+                .collect();
+            if let [part] = non_null[..]
+                && (key != "allOf" || parts.len() == 1)
+            {
+                let rust_type = self.rust_type(part, hint);
+                return if non_null.len() < parts.len() || schema_type(schema).1 {
+                    format!("Option<{}>", strip_option(&rust_type))
+                } else {
+                    rust_type
+                };
+            }
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+                return self
+                    .components
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| type_name(name));
+            }
+            let resolved = self.resolve(schema);
+            if resolved.get("$ref").is_none() {
+                return self.rust_type(resolved, hint);
+            }
+            self.uses_json_value = true;
+            return "serde_json::Value".to_owned();
+        }
+        let (kind, nullable) = schema_type(schema);
+        let format = schema.get("format").and_then(Value::as_str).unwrap_or("");
+        let rust_type = match kind {
+            "string" if format == "binary" => "Vec<u8>".to_owned(),
+            "string" => "String".to_owned(),
+            "integer" if format == "int32" => "i32".to_owned(),
+            "integer" => "i64".to_owned(),
+            "number" if format == "float" => "f32".to_owned(),
+            "number" => "f64".to_owned(),
+            "boolean" => "bool".to_owned(),
+            "array" => {
+                let items = schema.get("items").cloned().unwrap_or(Value::Null);
+                format!("Vec<{}>", self.rust_type(&items, &format!("{hint}Item")))
+            }
+            _ if is_object(schema) => {
+                let name = self.unique_name(hint.to_owned());
+                self.define_struct(name.clone(), &name, schema);
+                name
+            }
+            _ => match schema.get("additionalProperties") {
+                Some(values) if values.is_object() => format!(
+                    "std::collections::HashMap<String, {}>",
+                    self.rust_type(values, &format!("{hint}Value"))
+                ),
+                _ => {
+                    self.uses_json_value = true;
+                    "serde_json::Value".to_owned()
+                }
+            },
+        };
+        if nullable {
+            format!("Option<{rust_type}>")
+        } else {
+            rust_type
+        }
+    }
+}
+
+/// Objects with their own properties become structs, free-form objects become maps or JSON values.
+fn is_object(schema: &Value) -> bool {
+    schema.get("properties").is_some()
+        || schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .is_some_and(|parts| parts.len() > 1)
+}
+
+/// The schema type and whether it is nullable, for both OpenAPI 3.0 and 3.1 documents.
+fn schema_type(schema: &Value) -> (&str, bool) {
+    let nullable = schema.get("nullable").and_then(Value::as_bool) == Some(true);
+    match schema.get("type") {
+        Some(Value::String(kind)) => (kind, nullable),
+        Some(Value::Array(kinds)) => {
+            let kinds: Vec<&str> = kinds.iter().filter_map(Value::as_str).collect();
+            let kind = kinds.iter().find(|kind| **kind != "null").copied();
+            (kind.unwrap_or("null"), nullable || kinds.contains(&"null"))
+        }
+        _ => ("", nullable),
+    }
+}
+
+pub(super) fn strip_option(rust_type: &str) -> &str {
+    rust_type
+        .strip_prefix("Option<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .unwrap_or(rust_type)
+}
+
+#[derive(Debug, Serialize)]
+struct Operation {
+    module: String,
+    handler: String,
+    method: &'static str,
+    route: String,
+    secured: bool,
+    /// Quoted string literals for the `#[endpoint]` attribute.
+    operation_id: Option<String>,
+    tags: Vec<String>,
+    doc: Vec<String>,
+    args: Vec<String>,
+    /// The arguments go on their own lines when the signature is longer than rustfmt allows.
+    wrap_args: bool,
+    arg_names: Vec<String>,
+    return_type: String,
+}
+
+fn collect_operations(doc: &Value, types: &mut Types) -> Vec<Operation> {
+    let Some(paths) = doc.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut handlers = HashSet::new();
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = types.resolve(item);
+        for method in METHODS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let mut handler = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(plain_ident)
+                .unwrap_or_else(|| plain_ident(&format!("{method} {path}")));
+            if !handlers.insert(handler.clone()) {
+                handler = (2..)
+                    .map(|index| format!("{handler}_{index}"))
+                    .find(|candidate| handlers.insert(candidate.clone()))
+                    .expect("an unused handler name");
+            }
+            operations.push(build_operation(
+                doc, types, path, item, method, operation, handler,
+            ));
+        }
+    }
+    operations
+}
+
+fn build_operation(
+    doc: &Value,
+    types: &mut Types,
+    path: &str,
+    item: &Value,
+    method: &'static str,
+    operation: &Value,
+    handler: String,
+) -> Operation {
+    let type_prefix = type_name(&handler);
+    let tags: Vec<String> = operation
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_owned)
+        .collect();
+    let module = tags
+        .first()
+        .map(String::as_str)
+        .or_else(|| {
+            path.split('/')
+                .find(|segment| !segment.is_empty() && !segment.starts_with('{'))
+        })
+        .map(plain_ident)
+        .unwrap_or_else(|| "api".to_owned());
+
+    let parameters = parameters(types, item, operation);
+    let mut route = path.trim_start_matches('/').to_owned();
+    let mut args = Vec::new();
+    let mut arg_names = Vec::new();
+    let mut query = Vec::new();
+    for parameter in &parameters {
+        let name = parameter.get("name").and_then(Value::as_str).unwrap_or("");
+        let schema = parameter.get("schema").cloned().unwrap_or(Value::Null);
+        let hint = format!("{type_prefix}{}", type_name(name));
+        match parameter.get("in").and_then(Value::as_str).unwrap_or("") {
+            "path" => {
+                let ident = plain_ident(name);
+                // The extractor reads the segment named after the argument.
+                route = route.replace(&format!("{{{name}}}"), &format!("{{{ident}}}"));
+                let rust_type = types.rust_type(&schema, &hint);
+                args.push(format!("{ident}: PathParam<{}>", strip_option(&rust_type)));
+                arg_names.push(ident);
+            }
+            "query" => query.push(parameter.clone()),
+            location => warning(t!(
+                "warning_openapi_parameter_skipped",
+                name = name,
+                location = location,
+                operation = handler
+            )),
+        }
+    }
+    if !query.is_empty() {
+        add_query_args(types, &type_prefix, &query, &mut args, &mut arg_names);
+    }
+    if let Some(body) = operation.get("requestBody") {
+        let body = types.resolve(body);
+        let content = body.get("content").and_then(Value::as_object);
+        let json = content.and_then(|content| {
+            content
+                .iter()
+                .find(|(media, _)| media.ends_with("json"))
+                .map(|(_, value)| value)
+        });
+        let form = content.and_then(|content| {
+            content
+                .get("application/x-www-form-urlencoded")
+                .or_else(|| content.get("multipart/form-data"))
+        });
+        match (json, form) {
+            (Some(media), _) | (None, Some(media)) => {
+                let schema = media.get("schema").cloned().unwrap_or(Value::Null);
+                let extractor = if json.is_some() {
+                    "JsonBody"
+                } else {
+                    "FormBody"
+                };
+                let rust_type = types.rust_type(&schema, &format!("{type_prefix}Body"));
+                args.push(format!("body: {extractor}<{rust_type}>"));
+                arg_names.push("body".to_owned());
+            }
+            (None, None) => warning(t!(
+                "warning_openapi_parameter_skipped",
+                name = "requestBody",
+                location = "body",
+                operation = handler
+            )),
+        }
+    }
+
+    let return_type = success_schema(types, operation)
+        .map(|schema| {
+            format!(
+                "JsonResult<{}>",
+                types.rust_type(&schema, &format!("{type_prefix}Response"))
+            )
+        })
+        .unwrap_or_else(|| "EmptyResult".to_owned());
+    let security = operation.get("security").or_else(|| doc.get("security"));
+    let signature = format!(
+        "pub async fn {handler}({}) -> {return_type} {{",
+        args.join(", ")
+    );
+    Operation {
+        wrap_args: signature.len() > 100,
+        module,
+        handler,
+        method,
+        route,
+        secured: is_secured(security),
+        operation_id: operation
+            .get("operationId")
+            .and_then(Value::as_str)
+            .map(|id| format!("{id:?}")),
+        tags: tags.iter().map(|tag| format!("{tag:?}")).collect(),
+        doc: doc_lines(operation),
+        args,
+        arg_names,
+        return_type,
+    }
+}
+
+/// The parameters of an operation, which override the ones declared on its path.
+pub(super) fn parameters(types: &Types, item: &Value, operation: &Value) -> Vec<Value> {
+    let mut parameters: Vec<Value> = Vec::new();
+    for parameter in [item, operation]
+        .iter()
+        .filter_map(|value| value.get("parameters").and_then(Value::as_array))
+        .flatten()
+    {
+        let parameter = types.resolve(parameter).clone();
+        parameters.retain(|existing| {
+            existing.get("name") != parameter.get("name")
+                || existing.get("in") != parameter.get("in")
+        });
+        parameters.push(parameter);
+    }
+    parameters
+}
+
+/// Query parameters use `QueryParam` when their names are plain identifiers, a parameters struct otherwise.
+fn add_query_args(
+    types: &mut Types,
+    type_prefix: &str,
+    query: &[Value],
+    args: &mut Vec<String>,
+    arg_names: &mut Vec<String>,
+) {
+    let name_of = |parameter: &Value| {
+        parameter
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_owned()
+    };
+    let required_of =
+        |parameter: &Value| parameter.get("required").and_then(Value::as_bool) == Some(true);
+    if query.iter().all(|parameter| {
+        let name = name_of(parameter);
+        plain_ident(&name) == name
+    }) {
+        for parameter in query {
+            let name = name_of(parameter);
+            let schema = parameter.get("schema").cloned().unwrap_or(Value::Null);
+            let rust_type = types.rust_type(&schema, &format!("{type_prefix}{}", type_name(&name)));
+            args.push(format!(
+                "{name}: QueryParam<{}, {}>",
+                strip_option(&rust_type),
+                required_of(parameter)
+            ));
+            arg_names.push(name);
+        }
+        return;
+    }
+    let struct_name = types.unique_name(format!("{type_prefix}Query"));
+    let fields = query
+        .iter()
+        .map(|parameter| {
+            let name = name_of(parameter);
+            let ident = to_rust_ident(&to_snake_case(&name));
+            let schema = parameter.get("schema").cloned().unwrap_or(Value::Null);
+            let rust_type = types.rust_type(&schema, &format!("{struct_name}{}", type_name(&name)));
+            let optional = !required_of(parameter);
+            Field {
+                renamed: ident != name,
+                rust_type: if optional {
+                    format!("Option<{}>", strip_option(&rust_type))
+                } else {
+                    rust_type
+                },
+                doc: doc_lines(parameter),
+                name,
+                ident,
+                optional,
+            }
+        })
+        .collect();
+    types.items.push(TypeItem {
+        kind: "params",
+        name: struct_name.clone(),
+        schema_name: struct_name.clone(),
+        doc: Vec::new(),
+        fields,
+        variants: Vec::new(),
+        target: String::new(),
+    });
+    args.push(format!("query: {struct_name}"));
+    arg_names.push("query".to_owned());
+}
+
+/// The JSON schema of the first 2xx response, if it has one.
+pub(super) fn success_schema(types: &Types, operation: &Value) -> Option<Value> {
+    let responses = operation.get("responses")?.as_object()?;
+    let (_, response) = responses
+        .iter()
+        .filter(|(status, _)| status.starts_with('2'))
+        .min_by(|(a, _), (b, _)| a.cmp(b))?;
+    let response = types.resolve(response);
+    response
+        .get("content")?
+        .as_object()?
+        .iter()
+        .find(|(media, _)| media.ends_with("json"))
+        .and_then(|(_, media)| media.get("schema"))
+        .cloned()
+}
+
+/// An operation needs the auth hoop unless one of its security requirements is empty.
+pub(super) fn is_secured(security: Option<&Value>) -> bool {
+    let requirements = security.and_then(Value::as_array);
+    requirements.is_some_and(|requirements| {
+        !requirements.is_empty()
+            && requirements
+                .iter()
+                .all(|requirement| requirement.as_object().is_some_and(|r| !r.is_empty()))
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct Route<'a> {
+    path: &'a str,
+    secured: bool,
+    operations: Vec<&'a Operation>,
+}
+
+fn routes_object(operations: &[Operation], types: &Types) -> liquid::Object {
+    let mut routes: Vec<Route> = Vec::new();
+    for operation in operations {
+        match routes
+            .iter_mut()
+            .find(|route| route.path == operation.route && route.secured == operation.secured)
+        {
+            Some(route) => route.operations.push(operation),
+            None => routes.push(Route {
+                path: &operation.route,
+                secured: operation.secured,
+                operations: vec![operation],
+            }),
+        }
+    }
+
+    let defined: HashSet<&str> = types.items.iter().map(|item| item.name.as_str()).collect();
+    let mut models = Vec::new();
+    let mut crate_items = Vec::new();
+    for operation in operations {
+        let signature = format!("{} {}", operation.args.join(" "), operation.return_type);
+        for word in signature.split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_') {
+            if let Some(name) = defined.get(word)
+                && !models.contains(name)
+            {
+                models.push(*name);
+            }
+        }
+        if operation.secured {
+            crate_items.extend(["config", "hoops"]);
+        }
+        crate_items.push(if operation.return_type == "EmptyResult" {
+            "EmptyResult"
+        } else {
+            "JsonResult"
+        });
+    }
+    models.sort_unstable();
+    // Keep the order rustfmt uses, functions and modules before types.
+    crate_items.sort_unstable_by_key(|item| (item.starts_with(char::is_uppercase), *item));
+    crate_items.dedup();
+    liquid::object!({
+        "routes": liquid::model::to_value(&routes).expect("routes serialize"),
+        "models": models,
+        "crate_items": crate_items,
+        "extractors": operations
+            .iter()
+            .flat_map(|operation| &operation.args)
+            .any(|arg| arg.contains("Param<") || arg.contains("Body<")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ROUTES_BEGIN, ROUTES_END, Types, is_secured, replace_routes};
+
+    #[test]
+    fn test_rust_types_follow_the_schema() {
+        let doc = json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["id", "name"],
+                        "properties": {
+                            "id": {"type": "integer", "format": "int64"},
+                            "name": {"type": "string"},
+                            "tag": {"type": "string", "nullable": true},
+                            "ownerInfo": {"type": "object", "properties": {"email": {"type": "string"}}}
+                        }
+                    },
+                    "Status": {"type": "string", "enum": ["available", "sold-out"]},
+                    "Pets": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}}
+                }
+            }
+        });
+        let mut types = Types::new(&doc);
+        types.define_components();
+
+        let pet = types.items.iter().find(|item| item.name == "Pet").unwrap();
+        let fields: Vec<(&str, &str)> = pet
+            .fields
+            .iter()
+            .map(|field| (field.ident.as_str(), field.rust_type.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("id", "i64"),
+                ("name", "String"),
+                ("tag", "Option<String>"),
+                ("owner_info", "Option<PetOwnerInfo>"),
+            ]
+        );
+        assert!(types.items.iter().any(|item| item.name == "PetOwnerInfo"));
+        let status = types
+            .items
+            .iter()
+            .find(|item| item.name == "Status")
+            .unwrap();
+        assert_eq!(status.kind, "enum");
+        assert_eq!(status.variants[1].name, "SoldOut");
+        let pets = types.items.iter().find(|item| item.name == "Pets").unwrap();
+        assert_eq!(pets.target, "Vec<Pet>");
+        assert_eq!(
+            types.rust_type(&json!({"type": ["integer", "null"]}), "X"),
+            "Option<i64>"
+        );
+        assert!(!types.uses_json_value);
+        assert_eq!(types.rust_type(&json!({}), "X"), "serde_json::Value");
+        assert!(types.uses_json_value);
+    }
+
+    #[test]
+    fn test_security_requirements() {
+        assert!(is_secured(Some(&json!([{"bearerAuth": []}]))));
+        assert!(!is_secured(Some(&json!([{"bearerAuth": []}, {}]))));
+        assert!(!is_secured(Some(&json!([]))));
+        assert!(!is_secured(None));
+    }
+
+    #[test]
+    fn test_replace_routes_keeps_handlers() {
+        let existing = format!(
+            "{ROUTES_BEGIN}\nold routes\n{ROUTES_END}\n\npub async fn list_pets() {{\n    body\n}}\n"
+        );
+        let routes = format!("{ROUTES_BEGIN}\nnew routes\n{ROUTES_END}\n");
+        assert_eq!(
+            replace_routes(&existing, &routes),
+            format!("{routes}\npub async fn list_pets() {{\n    body\n}}\n")
+        );
+    }
+}
@@ -0,0 +1,558 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use liquid::Parser;
+use rust_i18n::t;
+use serde::Serialize;
+
+use super::introspect::{Column, Source, Table};
+use super::{insert_line, mount_router, write_file};
+use crate::manifest;
+use crate::namer::{singularize, to_pascal_case, to_rust_ident, to_snake_case};
+use crate::printer::{success, warning};
+use crate::templates::classic::selection::{DbLib, DbType};
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "./templates/generate/schema"]
+struct Template;
+
+/// The list helpers the routers use, added to projects created before the template shipped them.
+const PAGINATION: &str = include_str!("../../templates/classic/_base/src/pagination.rs");
+
+/// Tables the project template already ships models and routers for.
+const STARTER_TABLES: [&str; 1] = ["users"];
+
+/// Generates models, entities and CRUD routers for the tables of an existing database.
+pub fn generate(
+    project_path: &Path,
+    database_url: &str,
+    only_tables: &[String],
+    force: bool,
+) -> Result<()> {
+    let metadata = manifest::read(project_path)?;
+    if metadata.db_lib == DbLib::Mongodb {
+        anyhow::bail!(t!(
+            "error_generate_unsupported_db_lib",
+            db_lib = metadata.db_lib
+        ));
+    }
+    let source = Source::parse(database_url)?;
+    let models: Vec<TableModel> = source
+        .read_tables()?
+        .iter()
+        .filter(|table| {
+            if only_tables.is_empty() {
+                !STARTER_TABLES.contains(&table.name.as_str())
+            } else {
+                only_tables.contains(&table.name)
+            }
+        })
+        .filter_map(|table| {
+            let model = TableModel::new(table, source.db_type(), metadata.db_type);
+            if model.is_none() {
+                warning(t!("warning_table_skipped", table = table.name));
+            }
+            model
+        })
+        .collect();
+    if models.is_empty() {
+        anyhow::bail!(t!("error_no_tables"));
+    }
+
+    let src = project_path.join("src");
+    if !src.join("pagination.rs").exists() {
+        write_file(&src.join("pagination.rs"), PAGINATION, false)?;
+        insert_line(&src.join("main.rs"), "mod models", "mod pagination;")?;
+    }
+    let parser = liquid::ParserBuilder::with_stdlib().build()?;
+    for model in &models {
+        write_table(project_path, metadata.db_lib, model, &parser, force)?;
+    }
+    success(t!("generate_schema_done", count = models.len()));
+    Ok(())
+}
+
+fn write_table(
+    project_path: &Path,
+    db_lib: DbLib,
+    model: &TableModel,
+    parser: &Parser,
+    force: bool,
+) -> Result<()> {
+    let src = project_path.join("src");
+    let module = &model.module;
+    let render = |name: &str| -> Result<String> {
+        let file = Template::get(&format!("{db_lib}/{name}.liquid")).expect("file must exist");
+        let template = parser.parse(&String::from_utf8_lossy(&file.data))?;
+        Ok(template.render(&liquid::to_object(model)?)?)
+    };
+
+    match db_lib {
+        DbLib::SeaOrm => {
+            write_file(
+                &src.join(format!("entities/{module}.rs")),
+                &render("entity.rs")?,
+                force,
+            )?;
+            insert_line(
+                &src.join("entities/mod.rs"),
+                "pub mod ",
+                &format!("pub mod {module};"),
+            )?;
+            insert_line(
+                &src.join("entities/prelude.rs"),
+                "pub use ",
+                &format!("pub use super::{module}::Entity as {};", model.entity_name),
+            )?;
+        }
+        DbLib::Diesel => {
+            let schema_path = src.join("schema.rs");
+            let schema = fs::read_to_string(&schema_path).unwrap_or_default();
+            if !schema.contains(&format!("    {module} (")) {
+                let table = render("table.rs")?;
+                fs::write(&schema_path, format!("{}\n\n{table}", schema.trim_end()))?;
+            }
+        }
+        _ => {}
+    }
+    write_file(
+        &src.join(format!("models/{module}.rs")),
+        &render("model.rs")?,
+        force,
+    )?;
+    insert_line(
+        &src.join("models/mod.rs"),
+        "pub mod ",
+        &format!("pub mod {module};"),
+    )?;
+    if write_file(
+        &src.join(format!("routers/{module}.rs")),
+        &render("router.rs")?,
+        force,
+    )? {
+        mount_router(
+            &src.join("routers/mod.rs"),
+            module,
+            r#"Router::with_path("api")"#,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct TableModel {
+    table: String,
+    module: String,
+    /// The module name differs from the table name, diesel needs a `sql_name` for it.
+    table_renamed: bool,
+    struct_name: String,
+    entity_name: String,
+    singular: String,
+    id_param: String,
+    route_param: String,
+    primary_key: ColumnModel,
+    /// String keys are filled with a ULID on insert, integer keys are left to the database.
+    ulid_key: bool,
+    columns: Vec<ColumnModel>,
+    input_columns: Vec<ColumnModel>,
+    has_bool_columns: bool,
+    sql: Statements,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ColumnModel {
+    name: String,
+    ident: String,
+    variant: String,
+    rust_type: &'static str,
+    field_type: String,
+    diesel_type: String,
+    rbatis_type: String,
+    /// Rbatis drivers hand booleans back as integers, these need a lenient deserializer.
+    rbatis_deserialize_with: Option<&'static str>,
+    renamed: bool,
+    primary_key: bool,
+}
+
+/// Queries used by the sqlx and rbatis templates, written in the project's SQL dialect.
+#[derive(Debug, Serialize)]
+struct Statements {
+    select_one: String,
+    select: String,
+    count: String,
+    key: String,
+    /// The fields a list can be sorted by and their quoted columns, the key first.
+    sort_columns: Vec<SortColumn>,
+    insert: String,
+    update: String,
+    delete: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SortColumn {
+    field: String,
+    column: String,
+}
+
+impl TableModel {
+    /// Returns `None` for tables without a single-column primary key or without other columns.
+    fn new(table: &Table, source_type: DbType, project_type: DbType) -> Option<Self> {
+        let keys: Vec<&Column> = table.columns.iter().filter(|c| c.primary_key).collect();
+        let [key] = keys[..] else {
+            return None;
+        };
+        if table.columns.len() < 2 {
+            return None;
+        }
+        let key_type = ColumnType::of(source_type, &key.sql_type)?;
+        let ulid_key = match key_type {
+            ColumnType::Text => true,
+            ColumnType::SmallInt | ColumnType::Int | ColumnType::BigInt => false,
+            _ => return None,
+        };
+
+        let columns: Vec<ColumnModel> = table
+            .columns
+            .iter()
+            .map(|column| {
+                let column_type =
+                    ColumnType::of(source_type, &column.sql_type).unwrap_or_else(|| {
+                        warning(t!(
+                            "warning_column_type_unmapped",
+                            table = table.name,
+                            column = column.name,
+                            sql_type = column.sql_type
+                        ));
+                        ColumnType::Text
+                    });
+                ColumnModel::new(column, column_type, ulid_key)
+            })
+            .collect();
+        let primary_key = columns.iter().find(|c| c.primary_key)?.clone();
+        let input_columns: Vec<ColumnModel> =
+            columns.iter().filter(|c| !c.primary_key).cloned().collect();
+
+        let module = to_snake_case(&table.name);
+        let module = if crate::namer::is_keyword(&module) {
+            format!("{module}_")
+        } else {
+            module
+        };
+        let singular = singularize(&module);
+        let id_param = format!("{singular}_id");
+        Some(Self {
+            table: table.name.clone(),
+            struct_name: to_pascal_case(&singular),
+            entity_name: to_pascal_case(&module),
+            route_param: format!("{{{id_param}}}"),
+            sql: Statements::new(project_type, &table.name, &columns, ulid_key),
+            has_bool_columns: columns.iter().any(|c| c.rbatis_deserialize_with.is_some()),
+            table_renamed: module != table.name,
+            module,
+            singular,
+            id_param,
+            primary_key,
+            ulid_key,
+            columns,
+            input_columns,
+        })
+    }
+}
+
+impl ColumnModel {
+    fn new(column: &Column, column_type: ColumnType, ulid_key: bool) -> Self {
+        let snake = to_snake_case(&column.name);
+        let ident = to_rust_ident(&snake);
+        let rust_type = column_type.rust_type();
+        let (field_type, diesel_type) = if column.nullable {
+            (
+                format!("Option<{rust_type}>"),
+                format!("Nullable<{}>", column_type.diesel_type()),
+            )
+        } else {
+            (rust_type.to_owned(), column_type.diesel_type().to_owned())
+        };
+        // Rbatis skips `None` on insert, which lets the database assign integer keys.
+        let rbatis_type = if column.primary_key && !ulid_key {
+            format!("Option<{rust_type}>")
+        } else {
+            field_type.clone()
+        };
+        let rbatis_deserialize_with = match (column_type, column.nullable) {
+            (ColumnType::Bool, false) => Some("bool_from_int"),
+            (ColumnType::Bool, true) => Some("option_bool_from_int"),
+            _ => None,
+        };
+        Self {
+            name: column.name.clone(),
+            variant: to_pascal_case(&snake),
+            renamed: ident != column.name,
+            ident,
+            rust_type,
+            field_type,
+            diesel_type,
+            rbatis_type,
+            rbatis_deserialize_with,
+            primary_key: column.primary_key,
+        }
+    }
+}
+
+impl Statements {
+    fn new(db_type: DbType, table: &str, columns: &[ColumnModel], ulid_key: bool) -> Self {
+        let quote = |name: &str| match db_type {
+            DbType::Mysql => format!("`{name}`"),
+            _ => format!("\"{name}\""),
+        };
+        let placeholder = |index: usize| match db_type {
+            DbType::Mysql => "?".to_owned(),
+            _ => format!("${index}"),
+        };
+        let table = quote(table);
+        let key = columns.iter().find(|c| c.primary_key).expect("primary key");
+        let sort_columns = std::iter::once(key)
+            .chain(columns.iter().filter(|c| !c.primary_key))
+            .map(|c| SortColumn {
+                field: c.name.clone(),
+                column: quote(&c.name),
+            })
+            .collect();
+        let key = quote(&key.name);
+        let all = columns
+            .iter()
+            .map(|c| quote(&c.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let inserted: Vec<&ColumnModel> = columns
+            .iter()
+            .filter(|c| ulid_key || !c.primary_key)
+            .collect();
+        let updated: Vec<&ColumnModel> = columns.iter().filter(|c| !c.primary_key).collect();
+
+        Self {
+            select_one: format!("SELECT {all} FROM {table} WHERE {key} = {}", placeholder(1)),
+            select: format!("SELECT {all} FROM {table}"),
+            count: format!("SELECT COUNT(*) FROM {table}"),
+            insert: format!(
+                "INSERT INTO {table} ({}) VALUES ({})",
+                inserted
+                    .iter()
+                    .map(|c| quote(&c.name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (1..=inserted.len())
+                    .map(placeholder)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            update: format!(
+                "UPDATE {table} SET {} WHERE {key} = {}",
+                updated
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| format!("{} = {}", quote(&c.name), placeholder(i + 1)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                placeholder(updated.len() + 1)
+            ),
+            delete: format!("DELETE FROM {table} WHERE {key} = {}", placeholder(1)),
+            key,
+            sort_columns,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    SmallInt,
+    Int,
+    BigInt,
+    Float,
+    Double,
+    Bool,
+    Text,
+    Bytes,
+}
+
+impl ColumnType {
+    /// Maps a declared SQL type, returning `None` when there is no lossless Rust type.
+    fn of(db_type: DbType, sql_type: &str) -> Option<Self> {
+        let sql_type = sql_type.trim().to_ascii_lowercase();
+        match db_type {
+            // https://www.sqlite.org/datatype3.html#determination_of_column_affinity
+            DbType::Sqlite => {
+                if sql_type.contains("bool") {
+                    Some(Self::Bool)
+                } else if sql_type.contains("int") {
+                    Some(Self::BigInt)
+                } else if ["char", "clob", "text"]
+                    .iter()
+                    .any(|t| sql_type.contains(t))
+                {
+                    Some(Self::Text)
+                } else if sql_type.contains("blob") {
+                    Some(Self::Bytes)
+                } else if ["real", "floa", "doub"]
+                    .iter()
+                    .any(|t| sql_type.contains(t))
+                {
+                    Some(Self::Double)
+                } else {
+                    None
+                }
+            }
+            DbType::Postgres => match sql_type.as_str() {
+                "smallint" => Some(Self::SmallInt),
+                "integer" => Some(Self::Int),
+                "bigint" => Some(Self::BigInt),
+                "real" => Some(Self::Float),
+                "double precision" => Some(Self::Double),
+                "boolean" => Some(Self::Bool),
+                "text" | "character varying" | "character" | "name" => Some(Self::Text),
+                "bytea" => Some(Self::Bytes),
+                _ => None,
+            },
+            DbType::Mysql => {
+                if sql_type.contains("unsigned") {
+                    return None;
+                }
+                if sql_type == "tinyint(1)" {
+                    return Some(Self::Bool);
+                }
+                match sql_type.split(['(', ' ']).next().unwrap_or_default() {
+                    "bool" | "boolean" => Some(Self::Bool),
+                    "smallint" => Some(Self::SmallInt),
+                    "int" | "integer" | "mediumint" => Some(Self::Int),
+                    "bigint" => Some(Self::BigInt),
+                    "float" => Some(Self::Float),
+                    "double" | "real" => Some(Self::Double),
+                    "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" => {
+                        Some(Self::Text)
+                    }
+                    "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+                        Some(Self::Bytes)
+                    }
+                    _ => None,
+                }
+            }
+            DbType::Mongodb => None,
+        }
+    }
+
+    fn rust_type(self) -> &'static str {
+        match self {
+            Self::SmallInt => "i16",
+            Self::Int => "i32",
+            Self::BigInt => "i64",
+            Self::Float => "f32",
+            Self::Double => "f64",
+            Self::Bool => "bool",
+            Self::Text => "String",
+            Self::Bytes => "Vec<u8>",
+        }
+    }
+
+    fn diesel_type(self) -> &'static str {
+        match self {
+            Self::SmallInt => "SmallInt",
+            Self::Int => "Integer",
+            Self::BigInt => "BigInt",
+            Self::Float => "Float",
+            Self::Double => "Double",
+            Self::Bool => "Bool",
+            Self::Text => "Text",
+            Self::Bytes => "Binary",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnType, TableModel};
+    use crate::generate::introspect::{Column, Table};
+    use crate::templates::classic::selection::DbType;
+
+    fn column(name: &str, sql_type: &str, nullable: bool, primary_key: bool) -> Column {
+        Column {
+            name: name.into(),
+            sql_type: sql_type.into(),
+            nullable,
+            primary_key,
+        }
+    }
+
+    #[test]
+    fn test_column_types_follow_each_dialect() {
+        assert_eq!(
+            ColumnType::of(DbType::Sqlite, "INTEGER"),
+            Some(ColumnType::BigInt)
+        );
+        assert_eq!(
+            ColumnType::of(DbType::Sqlite, "VARCHAR(40)"),
+            Some(ColumnType::Text)
+        );
+        assert_eq!(ColumnType::of(DbType::Sqlite, "DATETIME"), None);
+        assert_eq!(
+            ColumnType::of(DbType::Postgres, "integer"),
+            Some(ColumnType::Int)
+        );
+        assert_eq!(
+            ColumnType::of(DbType::Postgres, "double precision"),
+            Some(ColumnType::Double)
+        );
+        assert_eq!(ColumnType::of(DbType::Postgres, "uuid"), None);
+        assert_eq!(
+            ColumnType::of(DbType::Mysql, "tinyint(1)"),
+            Some(ColumnType::Bool)
+        );
+        assert_eq!(
+            ColumnType::of(DbType::Mysql, "varchar(255)"),
+            Some(ColumnType::Text)
+        );
+        assert_eq!(ColumnType::of(DbType::Mysql, "int unsigned"), None);
+    }
+
+    #[test]
+    fn test_statements_use_the_project_dialect() {
+        let table = Table {
+            name: "tags".into(),
+            columns: vec![
+                column("id", "text", false, true),
+                column("name", "text", false, false),
+                column("type", "text", true, false),
+            ],
+        };
+        let model = TableModel::new(&table, DbType::Postgres, DbType::Postgres).unwrap();
+        assert!(model.ulid_key);
+        assert_eq!(model.struct_name, "Tag");
+        assert_eq!(model.input_columns[1].ident, "r#type");
+        assert_eq!(
+            model.sql.insert,
+            r#"INSERT INTO "tags" ("id", "name", "type") VALUES ($1, $2, $3)"#
+        );
+        assert_eq!(
+            model.sql.update,
+            r#"UPDATE "tags" SET "name" = $1, "type" = $2 WHERE "id" = $3"#
+        );
+
+        let model = TableModel::new(&table, DbType::Postgres, DbType::Mysql).unwrap();
+        assert_eq!(model.sql.select, "SELECT `id`, `name`, `type` FROM `tags`");
+        let sort_columns = model
+            .sql
+            .sort_columns
+            .iter()
+            .map(|c| (c.field.as_str(), c.column.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sort_columns,
+            [("id", "`id`"), ("name", "`name`"), ("type", "`type`")]
+        );
+
+        let keyless = Table {
+            name: "logs".into(),
+            columns: vec![column("message", "text", false, false)],
+        };
+        assert!(TableModel::new(&keyless, DbType::Sqlite, DbType::Sqlite).is_none());
+    }
+}
@@ -1,10 +1,11 @@
-use std::fs::File;
+use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use rust_i18n::t;
 
-use crate::utils;
+use crate::{printer, utils};
 
 pub fn init_repository(dir: &Path) -> Result<()> {
     if !dir.join(".git").exists() {
@@ -13,44 +14,147 @@ pub fn init_repository(dir: &Path) -> Result<()> {
         // See: https://github.com/libgit2/libgit2/issues/5130
         utils::create_dir_all(dir)?;
         git2::Repository::init(dir)?;
-        write_ignore_file(dir)?;
     }
     Ok(())
 }
 
-pub fn write_ignore_file(project_path: &Path) -> Result<()> {
+/// Commits every file that is not ignored, as `author` or else the user's git config.
+pub fn commit_all(dir: &Path, author: Option<(&str, &str)>, message: &str) -> Result<git2::Oid> {
+    let repo = git2::Repository::open(dir)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = match author {
+        Some((name, email)) => git2::Signature::now(name, email)?,
+        None => repo.signature().context(t!("error_git_commit_author"))?,
+    };
+    Ok(repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])?)
+}
+
+/// The files of `paths` present in `dir` that a commit would include.
+pub fn unignored<'a>(dir: &Path, paths: &[&'a str]) -> Result<Vec<&'a str>> {
+    let repo = git2::Repository::open(dir)?;
+    let mut found = Vec::new();
+    for &path in paths {
+        if dir.join(path).exists() && !repo.is_path_ignored(path)? {
+            found.push(path);
+        }
+    }
+    Ok(found)
+}
+
+/// Writes `entries` to the `.gitignore`, appending the missing ones to an existing file.
+pub fn write_ignore_file(project_path: &Path, entries: &[String]) -> Result<()> {
     let fp_ignore = project_path.join(".gitignore");
-    let mut fp_ignore_file = File::create(fp_ignore)?;
-    fp_ignore_file.write_all(b"/target\n/migration/target")?;
+    let existing = fs::read_to_string(&fp_ignore).unwrap_or_default();
+    let missing = entries
+        .iter()
+        .filter(|entry| !existing.lines().any(|line| line.trim() == entry.as_str()))
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut fp_ignore_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&fp_ignore)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        fp_ignore_file.write_all(b"\n")?;
+    }
+    for entry in missing {
+        writeln!(fp_ignore_file, "{entry}")?;
+    }
+    printer::file_written(&fp_ignore, None);
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
-    use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
-    use super::write_ignore_file;
-
-    fn unique_temp_dir() -> PathBuf {
-        let suffix = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("system time should be after unix epoch")
-            .as_nanos();
-        std::env::temp_dir().join(format!("salvo-cli-test-{}-{suffix}", std::process::id()))
-    }
+    use super::{commit_all, init_repository, unignored, write_ignore_file};
+    use crate::utils::unique_temp_dir;
 
     #[test]
     fn write_ignore_file_creates_expected_entries() {
-        let project_path = unique_temp_dir();
+        let project_path = unique_temp_dir("test");
         fs::create_dir_all(&project_path).expect("temp project directory should be created");
 
-        write_ignore_file(&project_path).expect(".gitignore should be written");
+        let entries = ["/target".to_owned(), ".env".to_owned()];
+        write_ignore_file(&project_path, &entries).expect(".gitignore should be written");
 
         let ignore_contents =
             fs::read_to_string(project_path.join(".gitignore")).expect(".gitignore should exist");
-        assert_eq!(ignore_contents, "/target\n/migration/target");
+        assert_eq!(ignore_contents, "/target\n.env\n");
+
+        fs::remove_dir_all(&project_path).expect("temp project directory should be removed");
+    }
+
+    #[test]
+    fn write_ignore_file_keeps_an_existing_file() {
+        let project_path = unique_temp_dir("test");
+        fs::create_dir_all(&project_path).expect("temp project directory should be created");
+        fs::write(project_path.join(".gitignore"), "# mine\n/target\n*.bak").unwrap();
+
+        let entries = ["/target".to_owned(), "/logs/".to_owned()];
+        write_ignore_file(&project_path, &entries).expect(".gitignore should be written");
+        write_ignore_file(&project_path, &entries).expect(".gitignore should be written");
+
+        let ignore_contents = fs::read_to_string(project_path.join(".gitignore")).unwrap();
+        assert_eq!(ignore_contents, "# mine\n/target\n*.bak\n/logs/\n");
+
+        fs::remove_dir_all(&project_path).expect("temp project directory should be removed");
+    }
+
+    #[test]
+    fn commit_all_commits_the_files_not_ignored_as_the_author() {
+        let project_path = unique_temp_dir("test");
+        init_repository(&project_path).expect("repository should be created");
+        write_ignore_file(&project_path, &["/target".to_owned()]).unwrap();
+        fs::write(project_path.join("Cargo.toml"), "[package]").unwrap();
+        fs::create_dir_all(project_path.join("target")).unwrap();
+        fs::write(project_path.join("target/build.log"), "").unwrap();
+
+        let oid = commit_all(
+            &project_path,
+            Some(("Ada", "ada@example.com")),
+            "Initial commit",
+        )
+        .expect("files should be committed");
+
+        let repo = git2::Repository::open(&project_path).unwrap();
+        let commit = repo.find_commit(oid).unwrap();
+        assert_eq!(commit.author().name().ok(), Some("Ada"));
+        assert_eq!(commit.author().email().ok(), Some("ada@example.com"));
+        assert_eq!(commit.message().ok(), Some("Initial commit"));
+        let tree = commit.tree().unwrap();
+        assert!(tree.get_name("Cargo.toml").is_some());
+        assert!(tree.get_name(".gitignore").is_some());
+        assert!(tree.get_name("target").is_none());
+
+        fs::remove_dir_all(&project_path).expect("temp project directory should be removed");
+    }
+
+    #[test]
+    fn unignored_lists_the_files_a_commit_would_include() {
+        let project_path = unique_temp_dir("test");
+        init_repository(&project_path).expect("repository should be created");
+        fs::write(project_path.join(".env"), "JWT_SECRET=abc").unwrap();
+        fs::create_dir_all(project_path.join("certs")).unwrap();
+        fs::write(project_path.join("certs/key.pem"), "").unwrap();
+        let secrets = [".env", "certs/key.pem", "missing.pem"];
+        assert_eq!(
+            unignored(&project_path, &secrets).unwrap(),
+            [".env", "certs/key.pem"]
+        );
+
+        write_ignore_file(
+            &project_path,
+            &["/.env".to_owned(), "/certs/key.pem".to_owned()],
+        )
+        .unwrap();
+        assert!(unignored(&project_path, &secrets).unwrap().is_empty());
 
         fs::remove_dir_all(&project_path).expect("temp project directory should be removed");
     }
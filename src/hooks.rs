@@ -0,0 +1,189 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use rust_i18n::t;
+use serde_json::json;
+
+use crate::printer::{self, gray, success, warning};
+use crate::templates::classic::SECRET_FILES;
+use crate::{Project, config, git};
+
+/// A step run in a new project once its files are written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, strum::Display, clap::ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum Hook {
+    /// `cargo fmt`, on by default.
+    #[strum(serialize = "cargo fmt")]
+    Fmt,
+    /// `cargo generate-lockfile`.
+    #[strum(serialize = "cargo generate-lockfile")]
+    Lockfile,
+    /// `cargo check`, which downloads and builds every dependency.
+    #[strum(serialize = "cargo check")]
+    Check,
+    /// An initial commit, on by default when a git repository is created.
+    #[strum(serialize = "git commit")]
+    Commit,
+}
+
+impl Hook {
+    /// Every hook, in the order they run. The commit comes last to include the lockfile.
+    const ALL: [Hook; 4] = [Hook::Fmt, Hook::Lockfile, Hook::Check, Hook::Commit];
+
+    fn is_default(self) -> bool {
+        matches!(self, Hook::Fmt | Hook::Commit)
+    }
+}
+
+/// The hooks to run: `--with` and `--without` win over the user config, which wins over the defaults.
+pub fn selected(config: &config::Hooks, with: &[Hook], without: &[Hook]) -> Vec<Hook> {
+    Hook::ALL
+        .into_iter()
+        .filter(|hook| {
+            if without.contains(hook) {
+                false
+            } else if with.contains(hook) {
+                true
+            } else {
+                config.get(*hook).unwrap_or_else(|| hook.is_default())
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Done,
+    Skipped(String),
+    Failed(String),
+}
+
+/// Runs the hooks of `proj` in the project, carrying on after failures.
+pub fn run(project_path: &Path, proj: &Project) -> Vec<(Hook, Outcome)> {
+    proj.hooks
+        .iter()
+        .map(|&hook| {
+            let outcome = if hook == Hook::Commit && !project_path.join(".git").exists() {
+                Outcome::Skipped(t!("hook_skipped_no_git").into())
+            } else if let Some(files) = exposed_secrets(hook, project_path) {
+                Outcome::Skipped(t!("hook_skipped_secrets", files = files).into())
+            } else {
+                gray(t!("hook_running", hook = hook));
+                match printer::step(&hook.to_string(), || run_hook(hook, project_path, proj)) {
+                    Ok(()) => Outcome::Done,
+                    Err(e) => Outcome::Failed(format!("{e:#}")),
+                }
+            };
+            (hook, outcome)
+        })
+        .collect()
+}
+
+/// The secret files an initial commit would include, which stops it.
+fn exposed_secrets(hook: Hook, project_path: &Path) -> Option<String> {
+    if hook != Hook::Commit {
+        return None;
+    }
+    let files = git::unignored(project_path, &SECRET_FILES).ok()?;
+    (!files.is_empty()).then(|| files.join(", "))
+}
+
+fn run_hook(hook: Hook, project_path: &Path, proj: &Project) -> Result<()> {
+    match hook {
+        Hook::Fmt => cargo(project_path, &["fmt"]),
+        Hook::Lockfile => cargo(project_path, &["generate-lockfile"]),
+        Hook::Check => cargo(project_path, &["check", "--quiet"]),
+        Hook::Commit => {
+            let author = proj
+                .git_author
+                .as_ref()
+                .map(|(name, email)| (name.as_str(), email.as_str()));
+            git::commit_all(project_path, author, "Initial commit")?;
+            Ok(())
+        }
+    }
+}
+
+fn cargo(project_path: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("cargo")
+        .args(args)
+        .current_dir(project_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(first_error(&String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(())
+}
+
+/// The first `error` line of cargo's output, or its last line.
+fn first_error(stderr: &str) -> String {
+    let mut lines = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    lines
+        .clone()
+        .find(|line| line.starts_with("error"))
+        .or_else(|| lines.next_back())
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Reports how each hook went, a `hook` event per hook in JSON mode.
+pub fn print_summary(outcomes: &[(Hook, Outcome)]) {
+    for (hook, outcome) in outcomes {
+        if printer::is_json() {
+            let (status, message) = match outcome {
+                Outcome::Done => ("done", None),
+                Outcome::Skipped(reason) => ("skipped", Some(reason)),
+                Outcome::Failed(error) => ("failed", Some(error)),
+            };
+            printer::event(
+                "hook",
+                json!({ "hook": hook.to_string(), "status": status, "message": message }),
+            );
+            continue;
+        }
+        match outcome {
+            Outcome::Done => success(t!("hook_done", hook = hook)),
+            Outcome::Skipped(reason) => gray(t!("hook_skipped", hook = hook, reason = reason)),
+            Outcome::Failed(error) => warning(t!("hook_failed", hook = hook, error = error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hook, first_error, selected};
+    use crate::config;
+
+    #[test]
+    fn test_flags_win_over_the_config_and_the_defaults() {
+        let none = config::Hooks::default();
+        assert_eq!(selected(&none, &[], &[]), [Hook::Fmt, Hook::Commit]);
+        assert_eq!(
+            selected(&none, &[Hook::Check], &[Hook::Fmt]),
+            [Hook::Check, Hook::Commit]
+        );
+
+        let config = config::Hooks {
+            lockfile: Some(true),
+            commit: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(selected(&config, &[], &[]), [Hook::Fmt, Hook::Lockfile]);
+        assert_eq!(
+            selected(&config, &[Hook::Commit], &[Hook::Lockfile]),
+            [Hook::Fmt, Hook::Commit]
+        );
+    }
+
+    #[test]
+    fn test_failures_show_the_first_cargo_error() {
+        let stderr = "    Checking demo v0.1.0\nerror[E0425]: cannot find value `x`\n  --> src/main.rs:1:1\n\nerror: could not compile `demo`\n";
+        assert_eq!(first_error(stderr), "error[E0425]: cannot find value `x`");
+        assert_eq!(first_error("warning: a\n  no toolchain\n"), "no toolchain");
+        assert_eq!(first_error(""), "");
+    }
+}
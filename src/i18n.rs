@@ -1,33 +1,242 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rust_i18n::t;
+
+use crate::printer::{gray, success, warning};
+
 const SUPPORTED_LANGUAGES: [&str; 17] = [
     "en", "zh_CN", "zh_TW", "fr", "ja", "es", "de", "ru", "it", "pt", "ko", "no", "is", "uk", "th",
     "el", "da",
 ];
+
+#[derive(rust_embed::RustEmbed)]
+#[folder = "./locales"]
+struct Locales;
+
+/// The supported locale closest to a language tag such as `de_DE.UTF-8`, `zh-Hant` or `nb`.
+fn negotiate(tag: &str) -> Option<&'static str> {
+    // Drop the encoding and modifier, `de_DE.UTF-8@euro` names the same language as `de_DE`.
+    let tag = tag.split(['.', '@']).next()?.replace('-', "_");
+    let mut parts = tag.split('_');
+    let language = parts.next()?.to_lowercase();
+    let rest: Vec<String> = parts.map(str::to_uppercase).collect();
+    let language = match language.as_str() {
+        "zh" => {
+            let traditional = rest
+                .iter()
+                .any(|part| matches!(part.as_str(), "HANT" | "TW" | "HK" | "MO"));
+            if traditional { "zh_TW" } else { "zh_CN" }
+        }
+        // Norwegian Bokmål and Nynorsk share the Norwegian messages.
+        "nb" | "nn" => "no",
+        language => language,
+    };
+    SUPPORTED_LANGUAGES
+        .into_iter()
+        .find(|supported| *supported == language)
+}
+
+/// The locale the environment asks for, `LC_ALL` over `LC_MESSAGES` over `LANG`.
+fn env_locale(var: impl Fn(&str) -> Option<String>) -> Option<&'static str> {
+    let tag = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(var)
+        .find(|value| !value.is_empty())?;
+    negotiate(&tag)
+}
+
+/// Parses `--code-lang`, which unlike the CLI language has no fallback.
+pub fn parse_code_lang(lang: &str) -> Result<String, String> {
+    negotiate(lang).map(str::to_owned).ok_or_else(|| {
+        format!(
+            "unsupported language `{lang}`, use one of: {}",
+            SUPPORTED_LANGUAGES.join(", ")
+        )
+    })
+}
+
 pub fn set_locale(language: &Option<String>) {
-    match language {
-        Some(lang) => {
-            let lang = if lang == "zh" { "zh_CN" } else { lang };
-            if SUPPORTED_LANGUAGES.contains(&lang) {
-                rust_i18n::set_locale(lang);
-            } else {
-                rust_i18n::set_locale("en");
+    let locale = match language {
+        Some(lang) => negotiate(lang),
+        None => env_locale(|name| std::env::var(name).ok()),
+    };
+    rust_i18n::set_locale(locale.unwrap_or("en"));
+}
+
+/// How much of one locale the message files cover.
+#[derive(Debug)]
+struct Coverage {
+    locale: &'static str,
+    total: usize,
+    /// `(file, key)` pairs without a translation.
+    missing: Vec<(String, String)>,
+}
+
+/// Checks every key of the message files for a non-empty translation in each supported locale.
+fn coverage(files: &[(String, String)]) -> Result<Vec<Coverage>> {
+    let mut keys = Vec::new();
+    for (name, content) in files {
+        let messages: BTreeMap<String, serde_yaml::Value> = serde_yaml::from_str(content)
+            .with_context(|| format!("invalid message file `{name}`"))?;
+        for (key, translations) in messages {
+            if !key.starts_with('_') {
+                keys.push((name.clone(), key, translations));
             }
         }
-        None => match get_language() {
-            Some(lang) => {
-                rust_i18n::set_locale(lang.as_str());
-            }
-            None => {
-                rust_i18n::set_locale("en");
+    }
+    Ok(SUPPORTED_LANGUAGES
+        .into_iter()
+        .map(|locale| Coverage {
+            locale,
+            total: keys.len(),
+            missing: keys
+                .iter()
+                .filter(|(_, _, translations)| {
+                    translations
+                        .get(locale)
+                        .and_then(|value| value.as_str())
+                        .is_none_or(|value| value.trim().is_empty())
+                })
+                .map(|(name, key, _)| (name.clone(), key.clone()))
+                .collect(),
+        })
+        .collect())
+}
+
+/// The message files built into the CLI, as `(file name, content)` pairs.
+fn built_in_files() -> Vec<(String, String)> {
+    Locales::iter()
+        .map(|name| {
+            let file = Locales::get(&name).expect("file must exist");
+            let content = String::from_utf8_lossy(&file.data).into_owned();
+            (name.into_owned(), content)
+        })
+        .collect()
+}
+
+/// Prints the missing messages of each locale, read from `dir` or from the messages built into the CLI.
+pub fn report(dir: Option<&Path>) -> Result<()> {
+    let mut files = match dir {
+        Some(dir) => {
+            let mut files = Vec::new();
+            let entries =
+                fs::read_dir(dir).with_context(|| format!("failed to read `{}`", dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "yml") {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    files.push((name.into_owned(), fs::read_to_string(&path)?));
+                }
             }
-        },
+            files
+        }
+        None => built_in_files(),
+    };
+    files.sort();
+
+    for coverage in coverage(&files)? {
+        if coverage.missing.is_empty() {
+            success(t!(
+                "i18n_report_complete",
+                language = coverage.locale,
+                total = coverage.total
+            ));
+            continue;
+        }
+        warning(t!(
+            "i18n_report_missing",
+            language = coverage.locale,
+            missing = coverage.missing.len(),
+            total = coverage.total
+        ));
+        for (file, key) in &coverage.missing {
+            gray(format!("    {file}: {key}"));
+        }
     }
+    Ok(())
 }
-fn get_language() -> Option<String> {
-    match std::env::var("LANG") {
-        Ok(lang) => {
-            let language = lang.split('.').next();
-            language.map(|s| s.to_string())
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{built_in_files, coverage, env_locale, negotiate};
+
+    #[test]
+    fn test_negotiate_falls_back_from_region_to_language() {
+        assert_eq!(negotiate("de_DE.UTF-8"), Some("de"));
+        assert_eq!(negotiate("fr-CA"), Some("fr"));
+        assert_eq!(negotiate("pt_BR.utf8@euro"), Some("pt"));
+        assert_eq!(negotiate("zh"), Some("zh_CN"));
+        assert_eq!(negotiate("zh_CN.UTF-8"), Some("zh_CN"));
+        assert_eq!(negotiate("zh_SG"), Some("zh_CN"));
+        assert_eq!(negotiate("zh-Hant"), Some("zh_TW"));
+        assert_eq!(negotiate("zh_Hant_HK"), Some("zh_TW"));
+        assert_eq!(negotiate("zh_HK"), Some("zh_TW"));
+        assert_eq!(negotiate("nb_NO"), Some("no"));
+        assert_eq!(negotiate("C"), None);
+        assert_eq!(negotiate("POSIX"), None);
+        assert_eq!(negotiate("xx_YY"), None);
+    }
+
+    #[test]
+    fn test_env_locale_prefers_lc_all_then_lc_messages_then_lang() {
+        let env = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            env_locale(|name| vars.get(name).cloned())
+        };
+        assert_eq!(env(&[]), None);
+        assert_eq!(env(&[("LANG", "ja_JP.UTF-8")]), Some("ja"));
+        assert_eq!(
+            env(&[("LANG", "ja_JP.UTF-8"), ("LC_MESSAGES", "ko_KR")]),
+            Some("ko")
+        );
+        assert_eq!(
+            env(&[
+                ("LANG", "ja_JP"),
+                ("LC_MESSAGES", "ko_KR"),
+                ("LC_ALL", "es_ES")
+            ]),
+            Some("es")
+        );
+        assert_eq!(env(&[("LANG", "ja_JP"), ("LC_ALL", "")]), Some("ja"));
+    }
+
+    #[test]
+    fn test_coverage_lists_missing_keys_per_locale() {
+        let files = [(
+            "cli.yml".to_owned(),
+            "_version: 2\nhello:\n  en: Hello\n  de: Hallo\nbye:\n  en: Bye\n  de: ''\n".to_owned(),
+        )];
+        let locales = coverage(&files).unwrap();
+        let en = locales.iter().find(|c| c.locale == "en").unwrap();
+        assert_eq!(en.total, 2);
+        assert!(en.missing.is_empty());
+        let de = locales.iter().find(|c| c.locale == "de").unwrap();
+        assert_eq!(de.missing, [("cli.yml".to_owned(), "bye".to_owned())]);
+        let fr = locales.iter().find(|c| c.locale == "fr").unwrap();
+        assert_eq!(fr.missing.len(), 2);
+
+        let broken = [("broken.yml".to_owned(), "- not a map".to_owned())];
+        assert!(coverage(&broken).is_err());
+    }
+
+    #[test]
+    fn test_built_in_messages_cover_every_locale() {
+        let files = built_in_files();
+        for locale in coverage(&files).unwrap() {
+            assert!(
+                locale.missing.is_empty(),
+                "{} misses {:?}",
+                locale.locale,
+                locale.missing
+            );
         }
-        Err(_) => None,
     }
 }
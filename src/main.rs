@@ -1,57 +1,489 @@
-#![recursion_limit = "256"]
+#![recursion_limit = "512"]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
 use anyhow::Result;
 use clap::Parser;
-mod tests;
-mod utils;
-use i18n::set_locale;
+use clap::builder::PossibleValuesParser;
+use clap_complete::Shell;
+use config::{Config, UpdateCheck};
+use hooks::Hook;
+use printer::{ColorChoice, Format};
+use templates::TemplateKind;
+use templates::classic::selection::{DbLib, DbType};
+mod certs;
+mod completions;
+mod config;
+mod db;
+mod doctor;
+mod generate;
 mod git;
+mod hooks;
 mod i18n;
+mod man;
+mod manifest;
 mod namer;
 mod printer;
 mod project;
 mod templates;
+mod tests;
 mod updater;
+mod utils;
 
 rust_i18n::i18n!("locales", fallback = "en");
 #[derive(Parser, Debug)]
-#[clap(version = env!("CARGO_PKG_VERSION"), author = "Fankai liu <liufankai137@outlook.com>")]
+#[clap(name = "salvo", version = env!("CARGO_PKG_VERSION"), author = "Fankai liu <liufankai137@outlook.com>")]
 struct Opts {
     #[clap(subcommand)]
     subcmd: SubCommand,
+    /// `json` prints one event per line: files written, warnings, errors and next steps.
+    #[clap(long, value_enum, default_value_t, global = true)]
+    format: Format,
+    /// Color the text output, `auto` leaving it plain when piped or `NO_COLOR` is set.
+    #[clap(long, value_enum, default_value_t, global = true)]
+    color: ColorChoice,
+    /// Only print warnings and errors, leaving out the logo and the files written.
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print the time each step takes, `-vv` also the template variables and paths.
+    #[clap(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
 }
 
 #[derive(Parser, Debug)]
 enum SubCommand {
     New(NewCmd),
+    /// Regenerate the self-signed TLS certificate of a project.
+    Certs(CertsCmd),
+    /// Generate code into an existing project.
+    Generate(GenerateCmd),
+    /// Run, revert and create database migrations of a project.
+    Db(DbCmd),
+    /// Check the translations of the CLI messages.
+    I18n(I18nCmd),
+    /// Read and change the defaults in the user config file.
+    Config(ConfigCmd),
+    /// Install the latest release of salvo-cli with cargo.
+    SelfUpdate(SelfUpdateCmd),
+    /// Check that the tools a project needs are installed.
+    Doctor(DoctorCmd),
+    /// Print the completion script of a shell, such as `salvo completions bash > /etc/bash_completion.d/salvo`.
+    Completions {
+        shell: Shell,
+    },
+    /// Print the man page, or write the pages of every subcommand into a folder.
+    Man {
+        #[clap(short, long)]
+        out_dir: Option<PathBuf>,
+    },
 }
 #[derive(Parser, Debug, Clone)]
 pub struct NewCmd {
     pub project_name: String,
+    /// Language of the CLI messages, taken from the environment by default.
+    #[clap(short = 'l', long = "ui-lang", visible_alias = "lang")]
+    ui_lang: Option<String>,
+    /// Language of the comments and text in the generated project, English by default.
+    #[clap(long, value_parser = i18n::parse_code_lang)]
+    code_lang: Option<String>,
+    /// Skip the database library prompt.
+    #[clap(long, value_enum)]
+    db_lib: Option<DbLib>,
+    /// Skip the database type prompt.
+    #[clap(long, value_enum)]
+    db_type: Option<DbType>,
+    #[clap(long, value_enum)]
+    template: Option<TemplateKind>,
+    /// Create a git repository, the default unless `git_init` is off in the user config.
+    #[clap(long, overrides_with = "no_git")]
+    git: bool,
+    #[clap(long, overrides_with = "git")]
+    no_git: bool,
+    /// Generate rate limiting for logins, a max body size and request timeouts, asked otherwise.
+    #[clap(long, overrides_with = "no_request_limits")]
+    request_limits: bool,
+    #[clap(long, overrides_with = "request_limits")]
+    no_request_limits: bool,
+    /// Also serve HTTP/3 when TLS is enabled, asked otherwise.
+    #[clap(long, overrides_with = "no_http3")]
+    http3: bool,
+    #[clap(long, overrides_with = "http3")]
+    no_http3: bool,
+    /// Username of the seeded admin account, asked otherwise.
+    #[clap(long)]
+    admin_username: Option<String>,
+    /// Read the admin password from the first line of stdin instead of asking for it.
+    /// `SALVO_ADMIN_PASSWORD` works too. Without either, a password is generated.
+    #[clap(long)]
+    admin_password_stdin: bool,
+    /// Steps to run after writing the files, on top of `fmt` and `commit`.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    with: Vec<Hook>,
+    /// Steps to leave out, such as `--without fmt,commit`.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    without: Vec<Hook>,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct CertsCmd {
+    #[clap(default_value = ".")]
+    pub project_path: PathBuf,
+    #[clap(short, long)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct GenerateCmd {
+    #[clap(subcommand)]
+    pub target: GenerateTarget,
+}
+#[derive(Parser, Debug, Clone)]
+pub enum GenerateTarget {
+    /// Generate models, entities and CRUD routers from an existing database schema.
+    Schema(SchemaCmd),
+    /// Generate schemas, handler stubs and routes from an OpenAPI 3 document.
+    Openapi(OpenapiCmd),
+    /// Generate a typed reqwest client crate for the API of a project.
+    Client(ClientCmd),
+}
+#[derive(Parser, Debug, Clone)]
+pub struct SchemaCmd {
+    /// A SQLite file path, or a sqlite://, postgres:// or mysql:// URL.
+    pub database_url: String,
+    /// Only generate code for these tables.
+    #[clap(short, long = "table")]
+    pub tables: Vec<String>,
+    #[clap(short, long, default_value = ".")]
+    pub project_path: PathBuf,
+    /// Overwrite generated files that already exist.
+    #[clap(long)]
+    pub force: bool,
     #[clap(short, long)]
     lang: Option<String>,
 }
-#[derive(Debug, Clone)]
+#[derive(Parser, Debug, Clone)]
+pub struct OpenapiCmd {
+    /// The OpenAPI document, in YAML or JSON.
+    pub spec: PathBuf,
+    #[clap(short, long, default_value = ".")]
+    pub project_path: PathBuf,
+    #[clap(short, long)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct ClientCmd {
+    /// Read the OpenAPI document from a file or an http(s) URL instead of exporting it from the project.
+    #[clap(long)]
+    pub from: Option<String>,
+    /// Where to write the client crate, `<project>/client` by default.
+    #[clap(short, long)]
+    pub out: Option<PathBuf>,
+    #[clap(short, long, default_value = ".")]
+    pub project_path: PathBuf,
+    #[clap(short, long)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct DbCmd {
+    #[clap(subcommand)]
+    pub action: DbAction,
+    #[clap(short, long, default_value = ".", global = true)]
+    pub project_path: PathBuf,
+    /// Use this database instead of `[db] url` in the project's config.toml.
+    #[clap(long, global = true)]
+    pub database_url: Option<String>,
+    #[clap(short, long, global = true)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub enum DbAction {
+    /// Apply all pending migrations.
+    Migrate,
+    /// Revert the most recently applied migrations.
+    Rollback {
+        /// How many migrations to revert.
+        #[clap(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// List the migrations and whether each one is applied.
+    Status,
+    /// Insert the rows of the seed files in `seeds`, skipping rows that already exist.
+    Seed,
+    /// Create an empty migration.
+    New {
+        /// A short description, such as `create_posts`.
+        name: String,
+    },
+}
+#[derive(Parser, Debug, Clone)]
+pub struct I18nCmd {
+    #[clap(subcommand)]
+    pub action: I18nAction,
+    #[clap(short, long, global = true)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub enum I18nAction {
+    /// List the messages each locale is missing.
+    Report {
+        /// Read the `*.yml` message files of this directory instead of the built-in ones.
+        #[clap(long)]
+        path: Option<PathBuf>,
+    },
+}
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigCmd {
+    #[clap(subcommand)]
+    pub action: ConfigAction,
+    #[clap(short, long, global = true)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the value of a key.
+    Get {
+        #[clap(value_parser = PossibleValuesParser::new(config::KEYS))]
+        key: String,
+    },
+    /// Set a key, such as `db_lib diesel` or `author.name "Jane Doe"`.
+    Set {
+        #[clap(value_parser = PossibleValuesParser::new(config::KEYS))]
+        key: String,
+        value: String,
+    },
+    /// Remove a key, going back to the built-in default.
+    Unset {
+        #[clap(value_parser = PossibleValuesParser::new(config::KEYS))]
+        key: String,
+    },
+    /// Print every key that is set.
+    List,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct SelfUpdateCmd {
+    /// Only report the latest release, without installing it.
+    #[clap(long)]
+    pub check: bool,
+    #[clap(short, long)]
+    lang: Option<String>,
+}
+#[derive(Parser, Debug, Clone)]
+pub struct DoctorCmd {
+    #[clap(short, long, default_value = ".")]
+    pub project_path: PathBuf,
+    /// Also write the report, with a fix for each failed check, to this Markdown file.
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+    #[clap(short, long)]
+    lang: Option<String>,
+}
+#[derive(Debug, Clone, Default)]
 pub struct Project {
     pub name: String,
-    pub lang: String,
+    pub template: TemplateKind,
+    pub code_lang: String,
+    /// Chosen by flags or the user config, asked for otherwise.
+    pub db_lib: Option<DbLib>,
+    pub db_type: Option<DbType>,
+    pub request_limits: Option<bool>,
+    pub http3: Option<bool>,
+    pub admin_username: Option<String>,
+    /// From stdin or `SALVO_ADMIN_PASSWORD`, asked for when `None`.
+    pub admin_password: Option<String>,
+    /// Written to the `authors` of the project's Cargo.toml.
+    pub author: Option<String>,
+    pub git_init: bool,
+    /// Run once the files are written, in order.
+    pub hooks: Vec<Hook>,
+    /// The name and email of the initial commit, the git config's when unset.
+    pub git_author: Option<(String, String)>,
 }
 #[tokio::main]
-async fn main() -> Result<()> {
-    printer::print_logo();
+async fn main() -> ExitCode {
     let opts: Opts = Opts::parse();
+    let level = printer::level(opts.quiet, opts.verbose);
+    printer::init(opts.format, opts.color, level);
+    // The logo would end up in the generated scripts and pages.
+    if !matches!(
+        opts.subcmd,
+        SubCommand::Completions { .. } | SubCommand::Man { .. }
+    ) {
+        printer::print_logo();
+    }
+    let start = Instant::now();
+    let result = run(opts).await;
+    log::debug!("finished in {:.1?}", start.elapsed());
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            printer::error(format!("{e:#}"));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The value of a `--x`/`--no-x` pair, `None` when neither is given.
+fn flag(yes: bool, no: bool) -> Option<bool> {
+    match (yes, no) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// The first line of stdin without its line ending.
+fn read_stdin_line() -> Result<String> {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+async fn run(opts: Opts) -> Result<()> {
+    let settings = config::load().unwrap_or_else(|e| {
+        printer::warning(format!("{e:#}"));
+        Config::default()
+    });
+    // `--lang` wins over the config, which wins over the environment.
+    let set_locale = |lang: Option<String>| i18n::set_locale(&lang.or(settings.lang.clone()));
     match opts.subcmd {
-        SubCommand::New(NewCmd { project_name, lang }) => {
-            set_locale(&lang);
+        SubCommand::New(NewCmd {
+            project_name,
+            ui_lang,
+            code_lang,
+            db_lib,
+            db_type,
+            template,
+            git,
+            no_git,
+            request_limits,
+            no_request_limits,
+            http3,
+            no_http3,
+            admin_username,
+            admin_password_stdin,
+            with,
+            without,
+        }) => {
+            set_locale(ui_lang);
+            if settings.update_check != Some(UpdateCheck::Never) {
+                let start = Instant::now();
+                updater::check_for_updates(&updater::Source::new(&settings)).await;
+                log::debug!("update check took {:.1?}", start.elapsed());
+            }
             let proj = Project {
                 name: project_name,
-                lang: lang.unwrap_or("en".to_string()),
-            };
-            updater::check_for_updates().await;
-            match project::create(&proj) {
-                Ok(_) => (),
-                Err(e) => printer::error(e.to_string()),
+                template: template.or(settings.template).unwrap_or_default(),
+                code_lang: code_lang
+                    .or(settings.code_lang)
+                    .unwrap_or_else(|| "en".into()),
+                db_lib: db_lib.or(settings.db_lib),
+                db_type: db_type.or(settings.db_type),
+                request_limits: flag(request_limits, no_request_limits).or(settings.request_limits),
+                http3: flag(http3, no_http3).or(settings.http3),
+                admin_username,
+                admin_password: if admin_password_stdin {
+                    Some(read_stdin_line()?)
+                } else {
+                    std::env::var("SALVO_ADMIN_PASSWORD").ok()
+                },
+                author: settings.author.to_cargo(),
+                git_init: git || (!no_git && settings.git_init.unwrap_or(true)),
+                hooks: hooks::selected(&settings.hooks, &with, &without),
+                git_author: settings
+                    .author
+                    .name
+                    .clone()
+                    .zip(settings.author.email.clone()),
             };
+            project::create(&proj)?;
+        }
+        SubCommand::Certs(CertsCmd { project_path, lang }) => {
+            set_locale(lang);
+            certs::regenerate(&project_path)?;
+        }
+        SubCommand::Generate(GenerateCmd { target }) => match target {
+            GenerateTarget::Schema(SchemaCmd {
+                database_url,
+                tables,
+                project_path,
+                force,
+                lang,
+            }) => {
+                set_locale(lang);
+                generate::schema::generate(&project_path, &database_url, &tables, force)?;
+            }
+            GenerateTarget::Openapi(OpenapiCmd {
+                spec,
+                project_path,
+                lang,
+            }) => {
+                set_locale(lang);
+                generate::openapi::generate(&project_path, &spec)?;
+            }
+            GenerateTarget::Client(ClientCmd {
+                from,
+                out,
+                project_path,
+                lang,
+            }) => {
+                set_locale(lang);
+                generate::client::generate(&project_path, from.as_deref(), out.as_deref()).await?;
+            }
+        },
+        SubCommand::Db(DbCmd {
+            action,
+            project_path,
+            database_url,
+            lang,
+        }) => {
+            set_locale(lang);
+            let database_url = database_url.as_deref();
+            match action {
+                DbAction::Migrate => db::migrate(&project_path, database_url),
+                DbAction::Rollback { steps } => db::rollback(&project_path, database_url, steps),
+                DbAction::Status => db::status(&project_path, database_url),
+                DbAction::Seed => db::seed(&project_path, database_url),
+                DbAction::New { name } => db::new(&project_path, &name),
+            }?;
+        }
+        SubCommand::Config(ConfigCmd { action, lang }) => {
+            set_locale(lang);
+            match action {
+                ConfigAction::Get { key } => config::get(&key),
+                ConfigAction::Set { key, value } => config::set(&key, &value),
+                ConfigAction::Unset { key } => config::unset(&key),
+                ConfigAction::List => config::list(),
+            }?;
+        }
+        SubCommand::SelfUpdate(SelfUpdateCmd { check, lang }) => {
+            set_locale(lang);
+            updater::self_update(&updater::Source::new(&settings), check).await?;
+        }
+        SubCommand::Doctor(DoctorCmd {
+            project_path,
+            output,
+            lang,
+        }) => {
+            set_locale(lang);
+            doctor::run(&project_path, output.as_deref())?;
+        }
+        SubCommand::Completions { shell } => completions::write(shell, &mut std::io::stdout())?,
+        SubCommand::Man { out_dir } => match out_dir {
+            Some(dir) => {
+                let pages = man::generate_to(&dir)?;
+                printer::success(rust_i18n::t!(
+                    "man_pages_written",
+                    count = pages.len(),
+                    path = dir.to_string_lossy()
+                ));
+            }
+            None => man::write(&mut std::io::stdout())?,
+        },
+        SubCommand::I18n(I18nCmd { action, lang }) => {
+            set_locale(lang);
+            match action {
+                I18nAction::Report { path } => i18n::report(path.as_deref()),
+            }?;
         }
     }
     Ok(())
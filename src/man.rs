@@ -0,0 +1,71 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::{Command, CommandFactory};
+use clap_mangen::Man;
+
+use crate::{Opts, printer, utils};
+
+fn command() -> Command {
+    let mut cmd = Opts::command().disable_help_subcommand(true);
+    // Names subcommands `salvo-new` and so on, like their pages.
+    cmd.build();
+    cmd
+}
+
+/// Renders the man page of `salvo` itself.
+pub fn write(out: &mut dyn Write) -> Result<()> {
+    Man::new(command()).render(out)?;
+    Ok(())
+}
+
+/// Writes the pages of `salvo` and every subcommand into `dir`, `salvo.1`, `salvo-new.1` and so on.
+pub fn generate_to(dir: &Path) -> Result<Vec<PathBuf>> {
+    fn generate(cmd: Command, dir: &Path, written: &mut Vec<PathBuf>) -> Result<()> {
+        for sub in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+            generate(sub.clone(), dir, written)?;
+        }
+        let path = Man::new(cmd).generate_to(dir)?;
+        printer::file_written(&path, None);
+        written.push(path);
+        Ok(())
+    }
+
+    utils::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    generate(command(), dir, &mut written)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{generate_to, write};
+    use crate::utils::unique_temp_dir;
+
+    #[test]
+    fn test_man_pages_are_rendered_for_every_subcommand() {
+        let mut page = Vec::new();
+        write(&mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with(".ie"), "{page}");
+        assert!(page.contains(".TH salvo 1"));
+        assert!(page.contains("salvo\\-doctor(1)"));
+
+        let dir = unique_temp_dir("man");
+        let written = generate_to(&dir).unwrap();
+        for name in [
+            "salvo.1",
+            "salvo-new.1",
+            "salvo-db-migrate.1",
+            "salvo-config-set.1",
+        ] {
+            assert!(written.contains(&dir.join(name)), "{name}: {written:?}");
+        }
+        let new = fs::read_to_string(dir.join("salvo-new.1")).unwrap();
+        assert!(new.contains("\\-\\-db\\-lib"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
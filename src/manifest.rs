@@ -0,0 +1,119 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use rust_i18n::t;
+use serde::Deserialize;
+
+use crate::templates::classic::selection::{DbLib, DbType};
+
+/// The `[package.metadata.salvo]` table that `salvo new` writes into a project's Cargo.toml.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ProjectMetadata {
+    pub db_lib: DbLib,
+    pub db_type: DbType,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+}
+#[derive(Deserialize)]
+struct Package {
+    name: Option<String>,
+    /// A table when inherited from a workspace.
+    #[serde(rename = "rust-version")]
+    rust_version: Option<toml::Value>,
+    metadata: Option<Metadata>,
+}
+#[derive(Deserialize)]
+struct Metadata {
+    salvo: Option<ProjectMetadata>,
+}
+
+pub fn read(project_path: &Path) -> Result<ProjectMetadata> {
+    let manifest_path = project_path.join("Cargo.toml");
+    load(project_path)?
+        .package
+        .and_then(|p| p.metadata)
+        .and_then(|m| m.salvo)
+        .ok_or_else(|| {
+            anyhow::anyhow!(t!(
+                "error_no_project_metadata",
+                path = manifest_path.to_string_lossy()
+            ))
+        })
+}
+
+/// The package name of a project, which is also its binary name.
+pub fn package_name(project_path: &Path) -> Result<String> {
+    let manifest_path = project_path.join("Cargo.toml");
+    load(project_path)?
+        .package
+        .and_then(|p| p.name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(t!(
+                "error_not_a_project",
+                path = manifest_path.to_string_lossy()
+            ))
+        })
+}
+
+/// The `rust-version` a project declares, if it spells one out.
+pub fn rust_version(project_path: &Path) -> Result<Option<String>> {
+    Ok(load(project_path)?
+        .package
+        .and_then(|p| p.rust_version)
+        .and_then(|version| version.as_str().map(str::to_owned)))
+}
+
+fn load(project_path: &Path) -> Result<Manifest> {
+    let manifest_path = project_path.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        anyhow::bail!(t!(
+            "error_not_a_project",
+            path = project_path.to_string_lossy()
+        ));
+    }
+    Ok(toml::from_str(&fs::read_to_string(&manifest_path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{package_name, read, rust_version};
+    use crate::templates::classic::selection::{DbLib, DbType};
+    use crate::utils::unique_temp_dir;
+
+    #[test]
+    fn read_returns_recorded_db_choices() {
+        let dir = unique_temp_dir("manifest");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n\n[package.metadata.salvo]\ndb_lib = \"seaorm\"\ndb_type = \"postgres\"\n",
+        )
+        .unwrap();
+        let metadata = read(&dir).unwrap();
+        assert_eq!(metadata.db_lib, DbLib::SeaOrm);
+        assert_eq!(metadata.db_type, DbType::Postgres);
+        assert_eq!(package_name(&dir).unwrap(), "demo");
+        assert_eq!(rust_version(&dir).unwrap(), None);
+
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nrust-version = \"1.94\"\n",
+        )
+        .unwrap();
+        assert!(read(&dir).is_err());
+        assert_eq!(rust_version(&dir).unwrap().as_deref(), Some("1.94"));
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nrust-version.workspace = true\n",
+        )
+        .unwrap();
+        assert_eq!(rust_version(&dir).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
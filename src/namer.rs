@@ -18,6 +18,70 @@ pub fn is_keyword(name: &str) -> bool {
     .contains(&name)
 }
 
+/// Converts a database identifier such as `BlogPosts` or `blog-posts` to `blog_posts`.
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+    let mut prev_lower = false;
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if ch.is_ascii_uppercase() && prev_lower {
+                snake.push('_');
+            }
+            prev_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+            snake.push(ch.to_ascii_lowercase());
+        } else {
+            if !snake.ends_with('_') {
+                snake.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let snake = snake.trim_matches('_');
+    if snake.starts_with(|ch: char| ch.is_ascii_digit()) {
+        format!("_{snake}")
+    } else {
+        snake.to_owned()
+    }
+}
+
+/// Converts a database identifier such as `blog_posts` to `BlogPosts`.
+pub fn to_pascal_case(name: &str) -> String {
+    to_snake_case(name)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A naive English singular, good enough for table names like `users` or `categories`.
+pub fn singularize(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if name.ends_with("sses") || name.ends_with("xes") || name.ends_with("ches") {
+        name[..name.len() - 2].to_owned()
+    } else if name.ends_with('s') && !name.ends_with("ss") && !name.ends_with("us") {
+        name[..name.len() - 1].to_owned()
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Makes a snake case name usable as a Rust identifier, using a raw identifier for keywords.
+pub fn to_rust_ident(name: &str) -> String {
+    match name {
+        "" => "_".to_owned(),
+        "self" | "Self" | "crate" | "super" => format!("{name}_"),
+        _ if is_keyword(name) => format!("r#{name}"),
+        _ => name.to_owned(),
+    }
+}
+
 /// These names cannot be used on Windows, even with an extension.
 pub fn is_windows_reserved(name: &str) -> bool {
     [
@@ -121,4 +185,19 @@ mod tests {
         assert!(is_non_ascii_name("测试"));
         assert!(!is_non_ascii_name("salvo"));
     }
+
+    #[test]
+    fn case_helpers_convert_table_names() {
+        assert_eq!(to_snake_case("BlogPosts"), "blog_posts");
+        assert_eq!(to_snake_case("order-items"), "order_items");
+        assert_eq!(to_snake_case("2fa codes"), "_2fa_codes");
+        assert_eq!(to_pascal_case("blog_posts"), "BlogPosts");
+        assert_eq!(singularize("categories"), "category");
+        assert_eq!(singularize("boxes"), "box");
+        assert_eq!(singularize("users"), "user");
+        assert_eq!(singularize("status"), "status");
+        assert_eq!(to_rust_ident("type"), "r#type");
+        assert_eq!(to_rust_ident("self"), "self_");
+        assert_eq!(to_rust_ident("title"), "title");
+    }
 }
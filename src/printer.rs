@@ -1,8 +1,184 @@
 #![allow(dead_code)]
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+
 use ansi_term::Colour;
 use ansi_term::Colour::{Blue, Green, Red, Yellow};
+use dialoguer::console;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rust_i18n::t;
+use serde_json::{Map, Value, json};
+
+/// How the CLI writes its output.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Colored messages for people.
+    #[default]
+    Text,
+    /// One JSON event per line, such as `{"event":"file_written","path":"..."}`.
+    Json,
+}
+
+/// When text output is colored.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color terminals unless `NO_COLOR` is set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Output {
+    format: Format,
+    color: ColorChoice,
+}
+
+static OUTPUT: OnceLock<Output> = OnceLock::new();
+
+/// Chooses the format, colors and level of everything printed afterwards, prompts included.
+pub fn init(format: Format, color: ColorChoice, level: LevelFilter) {
+    OUTPUT.get_or_init(|| Output { format, color });
+    if color != ColorChoice::Auto {
+        console::set_colors_enabled(color == ColorChoice::Always);
+        console::set_colors_enabled_stderr(color == ColorChoice::Always);
+    }
+    if log::set_logger(&Logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// The level of `-q`, no flag, `-v` and `-vv`.
+pub fn level(quiet: bool, verbose: u8) -> LevelFilter {
+    match (quiet, verbose) {
+        (true, _) => LevelFilter::Warn,
+        (false, 0) => LevelFilter::Info,
+        (false, 1) => LevelFilter::Debug,
+        (false, _) => LevelFilter::Trace,
+    }
+}
+
+fn output() -> Output {
+    OUTPUT.get().copied().unwrap_or_default()
+}
+
+pub fn is_json() -> bool {
+    output().format == Format::Json
+}
+
+/// Whether to color a stream, following <https://no-color.org> when `color` is `auto`.
+fn use_color(color: ColorChoice, no_color: Option<&str>, is_terminal: bool) -> bool {
+    match color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => no_color.is_none_or(str::is_empty) && is_terminal,
+    }
+}
+
+fn paint(colour: Colour, msg: &str, is_terminal: bool) -> String {
+    let no_color = std::env::var("NO_COLOR").ok();
+    if use_color(output().color, no_color.as_deref(), is_terminal) {
+        colour.paint(msg).to_string()
+    } else {
+        msg.to_owned()
+    }
+}
+
+/// The JSON line of an event, `fields` being an object merged after the `event` name.
+fn event_line(event: &str, fields: Value) -> String {
+    let mut object = Map::new();
+    object.insert("event".into(), event.into());
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    Value::Object(object).to_string()
+}
+
+/// Prints an event in JSON mode, nothing otherwise. Events are results and ignore `-q`.
+pub fn event(event: &str, fields: Value) {
+    if is_json() {
+        println!("{}", event_line(event, fields));
+    }
+}
+
+/// The targets of printer messages, records of other targets come from `debug!` and `trace!`.
+const TARGETS: [&str; 8] = [
+    "warning",
+    "error",
+    "success",
+    "orange",
+    "green",
+    "gray",
+    "plain",
+    "next_step",
+];
+
+/// The event name and color of a record. Printer messages use their kind as the target,
+/// `debug!` and `trace!` from other modules are shown in gray.
+fn kind(record: &Record) -> (&'static str, Option<Colour>) {
+    match (record.target(), record.level()) {
+        (_, Level::Error) => ("error", Some(Red)),
+        (_, Level::Warn) => ("warning", Some(Yellow)),
+        ("success", _) => ("success", Some(Blue)),
+        ("next_step", _) => ("next_step", Some(Blue)),
+        ("orange", _) => ("info", Some(Colour::RGB(255, 165, 0))),
+        ("green", _) => ("info", Some(Green)),
+        ("plain", _) => ("info", None),
+        (_, Level::Debug) => ("debug", Some(GRAY)),
+        (_, Level::Trace) => ("trace", Some(GRAY)),
+        (_, Level::Info) => ("info", Some(GRAY)),
+    }
+}
+
+const GRAY: Colour = Colour::RGB(128, 128, 128);
+
+/// Writes log records as text or JSON events, errors to stderr and everything else to stdout.
+struct Logger;
+
+impl Log for Logger {
+    /// Leaves out the records of dependencies, `-vv` would drown in the HTTP client's.
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let target = metadata.target();
+        metadata.level() <= log::max_level()
+            && (TARGETS.contains(&target) || target.starts_with(env!("CARGO_CRATE_NAME")))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let msg = record.args().to_string();
+        let (kind, colour) = kind(record);
+        let line = if is_json() {
+            event_line(kind, json!({ "message": msg }))
+        } else {
+            let is_terminal = if kind == "error" {
+                std::io::stderr().is_terminal()
+            } else {
+                std::io::stdout().is_terminal()
+            };
+            match colour {
+                Some(colour) => paint(colour, &msg, is_terminal),
+                None => msg,
+            }
+        };
+        if kind == "error" {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    fn flush(&self) {}
+}
 
 pub fn print_logo() {
+    if is_json() || !log::log_enabled!(Level::Info) {
+        return;
+    }
     let art = r"
   ____    _    _ __     _____
  / ___|  / \  | |\ \   / / _ \
@@ -10,30 +186,119 @@ pub fn print_logo() {
   ___) / ___ \| |__\ V /| |_| |
  |____/_/   \_\_____\_/  \___/
 ";
+    let is_terminal = std::io::stdout().is_terminal();
     let lines = art.lines();
     for line in lines {
         let (part_blue, part_green) = line.split_at(line.len() / 2);
-        println!("{}{}", Blue.paint(part_blue), Green.paint(part_green));
+        println!(
+            "{}{}",
+            paint(Blue, part_blue, is_terminal),
+            paint(Green, part_green, is_terminal)
+        );
     }
     println!(); // a new line
 }
 
+/// An empty line between blocks of text output.
+pub fn blank_line() {
+    if !is_json() && log::log_enabled!(Level::Info) {
+        println!();
+    }
+}
+
 pub fn warning<S: AsRef<str>>(msg: S) {
-    println!("{}", Yellow.paint(msg.as_ref()));
+    log::warn!(target: "warning", "{}", msg.as_ref());
 }
+/// Errors go to stderr in both formats.
 pub fn error<S: AsRef<str>>(msg: S) {
-    println!("{}", Red.paint(msg.as_ref()));
+    log::error!(target: "error", "{}", msg.as_ref());
 }
 pub fn success<S: AsRef<str>>(msg: S) {
-    println!("{}", Blue.paint(msg.as_ref()));
+    log::info!(target: "success", "{}", msg.as_ref());
 }
 pub fn orange<S: AsRef<str>>(msg: S) {
-    println!("{}", Colour::RGB(255, 165, 0).paint(msg.as_ref()));
+    log::info!(target: "orange", "{}", msg.as_ref());
 }
 pub fn green<S: AsRef<str>>(msg: S) {
-    println!("{}", Green.paint(msg.as_ref()));
+    log::info!(target: "green", "{}", msg.as_ref());
 }
 
 pub fn gray<S: AsRef<str>>(msg: S) {
-    println!("{}", Colour::RGB(128, 128, 128).paint(msg.as_ref()));
+    log::info!(target: "gray", "{}", msg.as_ref());
+}
+
+/// Uncolored text, such as the pending migrations of `salvo db status`.
+pub fn plain<S: AsRef<str>>(msg: S) {
+    log::info!(target: "plain", "{}", msg.as_ref());
+}
+
+/// What to do after a command, such as entering the new project.
+pub fn next_step<S: AsRef<str>>(msg: S) {
+    log::info!(target: "next_step", "{}", msg.as_ref());
+}
+
+/// A file was written, `msg` being what text output shows for it, if anything. Hidden by `-q`.
+pub fn file_written(path: &Path, msg: Option<String>) {
+    if !log::log_enabled!(Level::Info) {
+        return;
+    }
+    if is_json() {
+        event("file_written", json!({ "path": path }));
+    } else if let Some(msg) = msg {
+        gray(msg);
+    }
+}
+
+/// A generated file was written into an existing project.
+pub fn generated_file(path: &Path) {
+    let msg = t!("generated_file", path = path.to_string_lossy());
+    file_written(path, Some(msg.into()));
+}
+
+/// Runs one step of a command, logging how long it took with `-v`.
+pub fn step<T>(name: &str, run: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    log::debug!("{name} took {:.1?}", start.elapsed());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use log::LevelFilter;
+
+    use super::{ColorChoice, event_line, level, use_color};
+
+    #[test]
+    fn test_auto_color_follows_no_color_and_the_terminal() {
+        assert!(use_color(ColorChoice::Auto, None, true));
+        assert!(use_color(ColorChoice::Auto, Some(""), true));
+        assert!(!use_color(ColorChoice::Auto, Some("1"), true));
+        assert!(!use_color(ColorChoice::Auto, None, false));
+        assert!(use_color(ColorChoice::Always, Some("1"), false));
+        assert!(!use_color(ColorChoice::Never, None, true));
+    }
+
+    #[test]
+    fn test_event_line_puts_the_event_name_first() {
+        assert_eq!(
+            event_line("file_written", json!({ "path": "demo/Cargo.toml" })),
+            r#"{"event":"file_written","path":"demo/Cargo.toml"}"#
+        );
+        assert_eq!(
+            event_line("error", json!({ "message": "no \"project\"" })),
+            r#"{"event":"error","message":"no \"project\""}"#
+        );
+    }
+
+    #[test]
+    fn test_quiet_and_verbose_flags_pick_the_level() {
+        assert_eq!(level(false, 0), LevelFilter::Info);
+        assert_eq!(level(true, 0), LevelFilter::Warn);
+        assert_eq!(level(true, 2), LevelFilter::Warn);
+        assert_eq!(level(false, 1), LevelFilter::Debug);
+        assert_eq!(level(false, 3), LevelFilter::Trace);
+    }
 }
@@ -5,7 +5,11 @@ use std::{env, slice};
 use anyhow::{Context, Result};
 use rust_i18n::t;
 
-use crate::printer::{self, success, warning};
+use crate::hooks::{self, Hook, Outcome};
+use crate::printer::{self, next_step, success, warning};
+use crate::templates::TemplateKind;
+use crate::templates::classic;
+use crate::templates::classic::selection::Admin;
 use crate::{Project, namer};
 
 pub fn create(proj: &Project) -> Result<()> {
@@ -19,18 +23,32 @@ pub fn create(proj: &Project) -> Result<()> {
     }
 
     check_path(project_path)?;
-    crate::templates::classic::generate(proj)?;
-    after_print_info(&proj.name);
+    let selected = match proj.template {
+        TemplateKind::Classic => crate::templates::classic::generate(proj)?,
+    };
+    let outcomes = hooks::run(project_path, proj);
+    after_print_info(&proj.name, &selected.admin, &outcomes);
     Ok(())
 }
 
-fn after_print_info(project_name: &String) {
-    println!(); // a new line
+fn after_print_info(project_name: &String, admin: &Admin, outcomes: &[(Hook, Outcome)]) {
+    printer::blank_line();
+    hooks::print_summary(outcomes);
     success(t!("create_info", project_name = project_name).replace(r"\n", "\n"));
-    success(t!("create_success").replace(r"\n", "\n"));
-    success(t!("rust_version_tip"));
-    success(t!("cursor_rules_info").replace(r"\n", "\n"));
-    println!(); // a new line
+    next_step(t!("create_success").replace(r"\n", "\n"));
+    if admin.generated {
+        warning(t!(
+            "admin_account_generated",
+            username = admin.username,
+            password = admin.password
+        ));
+    } else {
+        success(t!("admin_account_created", username = admin.username));
+    }
+    let rust_version = classic::rust_version().unwrap_or_default();
+    next_step(t!("rust_version_tip", rust_version = rust_version));
+    next_step(t!("cursor_rules_info").replace(r"\n", "\n"));
+    printer::blank_line();
 }
 
 fn check_name(name: &str) -> Result<()> {
@@ -4,166 +4,292 @@ use std::io::Write;
 use std::path::Path;
 
 use anyhow::Result;
-use liquid::model::Object;
+use liquid::model::{Object, ValueView};
 use rust_i18n::t;
 
-use crate::printer::{gray, warning};
-use crate::{Project, git};
+use crate::printer::{self, warning};
+use crate::{Project, certs, db, git, utils};
 
 pub(crate) mod selection;
-use selection::Selected;
+use selection::{DbLib, DbType, Selected};
 
 #[derive(rust_embed::RustEmbed)]
 #[folder = "./templates/classic"]
 struct Template;
 
-pub fn generate(proj: &Project) -> Result<()> {
-    let Some(config) = selection::get_selected()? else {
+/// The `rust-version` of the projects this template generates.
+pub(crate) fn rust_version() -> Option<String> {
+    let file = Template::get("_base/Cargo.toml.liquid")?;
+    String::from_utf8_lossy(&file.data)
+        .lines()
+        .find_map(|line| line.strip_prefix("rust-version = "))
+        .map(|version| version.trim_matches('"').to_owned())
+}
+
+/// The directory `LogConfig` writes log files to unless `config.toml` says otherwise.
+const LOG_DIRECTORY: &str = "logs";
+
+/// The files holding the secrets generated for a project: its database password and JWT secret,
+/// and the private key of its certificate.
+pub(crate) const SECRET_FILES: [&str; 2] = [".env", "certs/key.pem"];
+
+/// What a clone or an image of the project leaves out: builds, logs, secrets and local data.
+fn ignore_entries(selected: &Selected) -> Vec<String> {
+    let mut entries = vec!["/target".to_owned()];
+    if selected.db_lib == DbLib::SeaOrm {
+        entries.push("/migration/target".into());
+    }
+    entries.push(format!("/{LOG_DIRECTORY}/"));
+    entries.extend(SECRET_FILES.map(|file| format!("/{file}")));
+    if selected.db_type == DbType::Sqlite {
+        // The database and its `-journal`, `-wal` and `-shm` files.
+        entries.push("/data/*.sqlite*".into());
+    }
+    entries
+}
+
+fn write_ignore_files(project_path: &Path, selected: &Selected) -> Result<()> {
+    let entries = ignore_entries(selected);
+    git::write_ignore_file(project_path, &entries)?;
+    let docker_ignore = project_path.join(".dockerignore");
+    let mut content = String::from("/.git\n");
+    for entry in &entries {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(&docker_ignore, content)?;
+    printer::file_written(&docker_ignore, None);
+    Ok(())
+}
+
+pub fn generate(proj: &Project) -> Result<Selected> {
+    let Some(config) = selection::get_selected(proj)? else {
         anyhow::bail!(t!("error_cli_quit"))
     };
     let project_path = Path::new(&proj.name);
-    match git::init_repository(project_path) {
-        Ok(_) => {}
-        Err(e) => {
-            warning(t!("warning_init_git", error = e).replace(r"\n", "\n"));
+    if proj.git_init {
+        match printer::step("git init", || git::init_repository(project_path)) {
+            Ok(_) => {}
+            Err(e) => {
+                warning(t!("warning_init_git", error = e).replace(r"\n", "\n"));
+            }
         }
     }
 
-    create_files(project_path, config, proj)?;
-    Ok(())
+    create_files(project_path, &config, proj)?;
+    Ok(config)
 }
 
-pub(crate) fn create_files(project_path: &Path, selected: Selected, proj: &Project) -> Result<()> {
+pub(crate) fn create_files(project_path: &Path, selected: &Selected, proj: &Project) -> Result<()> {
     let db_lib = selected.db_lib.to_string();
     let db_type = selected.db_type.to_string();
+    // Text baked into the project follows `--code-lang`, not the language of the CLI messages.
+    let code_lang = proj.code_lang.as_str();
+    let admin_id = if selected.db_lib == DbLib::Mongodb {
+        utils::random_object_id()
+    } else {
+        utils::random_uuid()
+    };
+    // Escaped for the TOML string in Cargo.toml.
+    let author = proj
+        .author
+        .as_deref()
+        .unwrap_or_default()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
     let data = liquid::object!({
         "project_name": proj.name,
+        "crate_name": proj.name.replace('-', "_"),
+        "author": author,
         "db_type":db_type,
         "db_lib":db_lib,
-        "main_log_message":t!("main_log_message"),
-        "config_error_no_exits":t!("config_error_no_exits"),
-        "config_error_read":t!("config_error_read"),
-        "config_error_parse":t!("config_error_parse"),
-        "config_error_read_failed":t!("config_error_read_failed"),
-        "username":t!("username"),
-        "password":t!("password"),
-        "incorrect_password":t!("incorrect_password"),
-        "login":t!("login"),
-        "user":t!("user"),
-        "add_user":t!("add_user"),
-        "lang":t!("lang"),
-        "rbatis_website":t!("rbatis_website"),
-        "account":t!("account"),
-        "password":t!("password"),
-        "you_wont_be_able_to_revert_this":t!("you_wont_be_able_to_revert_this"),
-        "search_placeholder":t!("search_placeholder"),
-        "search":t!("search"),
-        "previous_page":t!("previous_page"),
-        "page":t!("page"),
-        "total_records":t!("total_records"),
-        "page_size":t!("page_size"),
-        "update":t!("update"),
-        "delete":t!("delete"),
-        "next_page":t!("next_page"),
-        "talk_to_me_lang":t!("talk_to_me_lang"),
-        "salvo_cli_welcome":t!("salvo_cli_welcome"),
-        "user_list":t!("user_list"),
-        "are_you_sure_you_want_to_delete":t!("are_you_sure_you_want_to_delete"),
-        "page_not_found":t!("page_not_found"),
-        "contact_support":t!("contact_support"),
-        "return_to_homepage":t!("return_to_homepage"),
-        "delete":t!("delete"),
-        "yes":t!("yes"),
-        "cancel":t!("cancel"),
-        "open_api_page":t!("open_api_page"),
-        "login_page":t!("login_page"),
-        "operation":t!("operation"),
-        "create_success_seaorm__mysql_or_pgsql_install_seaorm":t!("create_success_seaorm__mysql_or_pgsql_install_seaorm"),
-        "create_success_mysql_or_pgsql_fist_use":t!("create_success_mysql_or_pgsql_fist_use").replace(r"\n", "\n"),
-        "create_success_seaorm__mysql_or_pgsql_fist_use":t!("create_success_seaorm__mysql_or_pgsql_fist_use").replace(r"\n", "\n"),
-        "create_success_diesel__mysql_or_pgsql_fist_use":t!("create_success_diesel__mysql_or_pgsql_fist_use").replace(r"\n", "\n"),
-        "is_starting": t!("is_starting"),
-        "listen_on": t!("listen_on"),
-        "database_connection_failed": t!("database_connection_failed"),
-        "user_does_not_exist": t!("user_does_not_exist"),
-        "rust_version_tip": t!("rust_version_tip"),
-        "introduction_title": t!("introduction_title"),
-        "introduction_content": t!("introduction_content"),
-        "seleted_sqlite": t!("seleted_sqlite"),
-        "run_the_project": t!("run_the_project"),
-        "run_the_tests": t!("run_the_tests"),
-        "sqlx_cli": t!("sqlx_cli"),
-        "about_salvo": t!("about_salvo"),
-        "about_salvo_text": t!("about_salvo_text"),
-        "tip_title": t!("tip_title"),
-        "password_tip": t!("password_tip"),
-        "config_tip": t!("config_tip"),
-        "orm_title": t!("orm_title"),
-        "sqlx_website": t!("sqlx_website"),
-        "seaorm_website": t!("seaorm_website"),
-        "diesel_website": t!("diesel_website"),
-        "mongodb_website": t!("mongodb_website"),
-        "mongodb_usage_import_user_data": t!("mongodb_usage_import_user_data"),
-        "initialization": t!("initialization"),
-        "initialization_sqlx_cli_not_sqlite": t!("initialization_sqlx_cli_not_sqlite").replace(r"\n", "\n"),
-        "initialization_seaorm_cli_not_sqlite": t!("initialization_seaorm_cli_not_sqlite").replace(r"\n", "\n"),
-        "initialization_diesel_cli_not_sqlite": t!("initialization_diesel_cli_not_sqlite").replace(r"\n", "\n"),
-        "initialization_rbatis_cli_not_sqlite": t!("initialization_rbatis_cli_not_sqlite").replace(r"\n", "\n"),
-        "seaorm_cli_website": t!("seaorm_cli_website").replace(r"\n", "\n"),
-        "diesel_cli_website": t!("diesel_cli_website").replace(r"\n", "\n"),
-        "onion_model_middleware": t!("onion_model_middleware"),
-        "skip_remaining_handlers": t!("skip_remaining_handlers"),
-        "pre_processing_middleware": t!("pre_processing_middleware"),
-        "post_processing_middleware": t!("post_processing_middleware"),
-        "acme_support": t!("acme_support"),
-        "rust_tip": t!("rust_tip").replace(r"\n", "\n"),
+        "request_limits":selected.request_limits,
+        "log_directory": LOG_DIRECTORY,
+        "http3":selected.http3,
+        "jwt_secret":utils::random_string(64),
+        "db_password":utils::random_string(24),
+        "admin_id":admin_id,
+        "admin_username":selected.admin.username,
+        "admin_password_hash":utils::hash_password(&selected.admin.password)?,
+        "main_log_message":t!("main_log_message", locale = code_lang),
+        "config_error_no_exits":t!("config_error_no_exits", locale = code_lang),
+        "config_error_read":t!("config_error_read", locale = code_lang),
+        "config_error_parse":t!("config_error_parse", locale = code_lang),
+        "config_error_read_failed":t!("config_error_read_failed", locale = code_lang),
+        "username":t!("username", locale = code_lang),
+        "password":t!("password", locale = code_lang),
+        "incorrect_password":t!("incorrect_password", locale = code_lang),
+        "login":t!("login", locale = code_lang),
+        "user":t!("user", locale = code_lang),
+        "add_user":t!("add_user", locale = code_lang),
+        "lang":t!("lang", locale = code_lang),
+        "rbatis_website":t!("rbatis_website", locale = code_lang),
+        "account":t!("account", locale = code_lang),
+        "password":t!("password", locale = code_lang),
+        "you_wont_be_able_to_revert_this":t!("you_wont_be_able_to_revert_this", locale = code_lang),
+        "search_placeholder":t!("search_placeholder", locale = code_lang),
+        "search":t!("search", locale = code_lang),
+        "previous_page":t!("previous_page", locale = code_lang),
+        "page":t!("page", locale = code_lang),
+        "total_records":t!("total_records", locale = code_lang),
+        "page_size":t!("page_size", locale = code_lang),
+        "update":t!("update", locale = code_lang),
+        "delete":t!("delete", locale = code_lang),
+        "next_page":t!("next_page", locale = code_lang),
+        "talk_to_me_lang":t!("talk_to_me_lang", locale = code_lang),
+        "salvo_cli_welcome":t!("salvo_cli_welcome", locale = code_lang),
+        "user_list":t!("user_list", locale = code_lang),
+        "are_you_sure_you_want_to_delete":t!("are_you_sure_you_want_to_delete", locale = code_lang),
+        "page_not_found":t!("page_not_found", locale = code_lang),
+        "contact_support":t!("contact_support", locale = code_lang),
+        "return_to_homepage":t!("return_to_homepage", locale = code_lang),
+        "delete":t!("delete", locale = code_lang),
+        "yes":t!("yes", locale = code_lang),
+        "cancel":t!("cancel", locale = code_lang),
+        "open_api_page":t!("open_api_page", locale = code_lang),
+        "login_page":t!("login_page", locale = code_lang),
+        "operation":t!("operation", locale = code_lang),
+        "create_success_seaorm__mysql_or_pgsql_install_seaorm":t!("create_success_seaorm__mysql_or_pgsql_install_seaorm", locale = code_lang),
+        "create_success_seaorm__mysql_or_pgsql_fist_use":t!("create_success_seaorm__mysql_or_pgsql_fist_use", locale = code_lang).replace(r"\n", "\n"),
+        "create_success_diesel__mysql_or_pgsql_fist_use":t!("create_success_diesel__mysql_or_pgsql_fist_use", locale = code_lang).replace(r"\n", "\n"),
+        "is_starting": t!("is_starting", locale = code_lang),
+        "listen_on": t!("listen_on", locale = code_lang),
+        "openapi_exported": t!("openapi_exported", locale = code_lang),
+        "database_connection_failed": t!("database_connection_failed", locale = code_lang),
+        "user_does_not_exist": t!("user_does_not_exist", locale = code_lang),
+        "rust_version_tip": t!("rust_version_tip", locale = code_lang, rust_version = rust_version().unwrap_or_default()),
+        "introduction_title": t!("introduction_title", locale = code_lang),
+        "introduction_content": t!("introduction_content", locale = code_lang),
+        "seleted_sqlite": t!("seleted_sqlite", locale = code_lang),
+        "run_the_project": t!("run_the_project", locale = code_lang),
+        "run_the_tests": t!("run_the_tests", locale = code_lang),
+        "export_the_openapi_spec": t!("export_the_openapi_spec", locale = code_lang),
+        "salvo_db_intro": t!("salvo_db_intro", locale = code_lang),
+        "show_migration_status": t!("show_migration_status", locale = code_lang),
+        "apply_pending_migrations": t!("apply_pending_migrations", locale = code_lang),
+        "roll_back_last_migration": t!("roll_back_last_migration", locale = code_lang),
+        "create_a_migration": t!("create_a_migration", locale = code_lang),
+        "seed_the_database": t!("seed_the_database", locale = code_lang),
+        "run_the_database_tests": t!("run_the_database_tests", locale = code_lang),
+        "sqlx_cli": t!("sqlx_cli", locale = code_lang),
+        "about_salvo": t!("about_salvo", locale = code_lang),
+        "about_salvo_text": t!("about_salvo_text", locale = code_lang),
+        "tip_title": t!("tip_title", locale = code_lang),
+        "password_tip": t!("password_tip", locale = code_lang, username = selected.admin.username),
+        "config_tip": t!("config_tip", locale = code_lang),
+        "orm_title": t!("orm_title", locale = code_lang),
+        "sqlx_website": t!("sqlx_website", locale = code_lang),
+        "seaorm_website": t!("seaorm_website", locale = code_lang),
+        "diesel_website": t!("diesel_website", locale = code_lang),
+        "mongodb_website": t!("mongodb_website", locale = code_lang),
+        "mongodb_usage_import_user_data": t!("mongodb_usage_import_user_data", locale = code_lang),
+        "initialization": t!("initialization", locale = code_lang),
+        "initialization_sqlx_cli_not_sqlite": t!("initialization_sqlx_cli_not_sqlite", locale = code_lang).replace(r"\n", "\n"),
+        "initialization_seaorm_cli_not_sqlite": t!("initialization_seaorm_cli_not_sqlite", locale = code_lang).replace(r"\n", "\n"),
+        "initialization_diesel_cli_not_sqlite": t!("initialization_diesel_cli_not_sqlite", locale = code_lang).replace(r"\n", "\n"),
+        "initialization_rbatis_cli_not_sqlite": t!("initialization_rbatis_cli_not_sqlite", locale = code_lang).replace(r"\n", "\n"),
+        "seaorm_cli_website": t!("seaorm_cli_website", locale = code_lang).replace(r"\n", "\n"),
+        "diesel_cli_website": t!("diesel_cli_website", locale = code_lang).replace(r"\n", "\n"),
+        "onion_model_middleware": t!("onion_model_middleware", locale = code_lang),
+        "skip_remaining_handlers": t!("skip_remaining_handlers", locale = code_lang),
+        "pre_processing_middleware": t!("pre_processing_middleware", locale = code_lang),
+        "post_processing_middleware": t!("post_processing_middleware", locale = code_lang),
+        "rust_tip": t!("rust_tip", locale = code_lang).replace(r"\n", "\n"),
     });
 
-    let db_lib_str = &*selected.db_lib.to_string();
-    for filename in Template::iter() {
-        if filename.starts_with("_base/") {
-            let file = Template::get(filename.as_ref()).expect("file must exist");
-            let file_path = project_path.join(filename.as_ref().trim_start_matches("_base/"));
-            write_file(&file.data, &file_path, &data)?;
-        } else if filename.starts_with("_data/") {
-            if filename.contains(db_lib_str) && filename.contains(".sqlite") && db_type == "sqlite"
-            {
-                let file = Template::get(filename.as_ref()).expect("file must exist");
-                let file_path = project_path.join(filename.as_ref().trim_start_matches("_"));
-                write_file(&file.data, &file_path, &data)?;
-            }
-        } else if filename.starts_with(&format!("{}/", db_lib_str)) {
-            let file = Template::get(filename.as_ref()).expect("file must exist");
-            let file_path = project_path.join(
-                filename
-                    .as_ref()
-                    .trim_start_matches(&format!("{}/", db_lib_str)),
-            );
-            write_file(&file.data, &file_path, &data)?;
+    for (key, value) in data.iter() {
+        // Secrets stay out of logs that get pasted into issues.
+        if ["jwt_secret", "db_password", "admin_password_hash"].contains(&key.as_str()) {
+            log::trace!("{key} = <redacted>");
+        } else {
+            log::trace!("{key} = {:?}", value.to_kstr());
         }
     }
 
+    printer::step("render templates", || {
+        write_templates(project_path, selected, &data)
+    })?;
+    sort_openapi_schemas(&project_path.join("openapi.json"))?;
+    printer::step("write ignore files", || {
+        write_ignore_files(project_path, selected)
+    })?;
+    printer::step("write certificates", || {
+        certs::write_self_signed(project_path)
+    })?;
+    if selected.db_type == DbType::Sqlite {
+        printer::step("create starter database", || {
+            db::create_starter(project_path, selected.db_lib)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Orders the schemas of the rendered OpenAPI document like salvo does, by their full name, which
+/// starts with the crate name.
+fn sort_openapi_schemas(path: &Path) -> Result<()> {
+    let mut spec: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if let Some(schemas) = spec
+        .pointer_mut("/components/schemas")
+        .and_then(|schemas| schemas.as_object_mut())
+    {
+        schemas.sort_keys();
+    }
+    fs::write(path, serde_json::to_string_pretty(&spec)? + "\n")?;
+    Ok(())
+}
+
+fn write_templates(project_path: &Path, selected: &Selected, data: &Object) -> Result<()> {
+    let db_lib_prefix = format!("{}/", selected.db_lib);
+    for filename in Template::iter() {
+        if !is_file_selected(&filename, selected) {
+            continue;
+        }
+        let Some(relative) = filename
+            .strip_prefix("_base/")
+            .or_else(|| filename.strip_prefix(&db_lib_prefix))
+        else {
+            continue;
+        };
+        let file = Template::get(filename.as_ref()).expect("file must exist");
+        let file_path = project_path.join(relative);
+        log::trace!("template {filename} -> {}", file_path.display());
+        write_file(&file.data, &file_path, data)?;
+    }
     Ok(())
 }
 
+/// Files only rendered when the matching option is selected.
+fn is_file_selected(filename: &str, selected: &Selected) -> bool {
+    match filename {
+        "_base/src/config/limit_config.rs" | "_base/src/hoops/limiter.rs" => {
+            selected.request_limits
+        }
+        // MongoDB and Rbatis document their routes a little differently and ship their own spec.
+        "_base/openapi.json.liquid" => !matches!(selected.db_lib, DbLib::Mongodb | DbLib::Rbatis),
+        _ => true,
+    }
+}
+
 fn write_file(tmpl: &[u8], file_path: &Path, data: &Object) -> Result<()> {
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)?;
     }
     if file_path.extension() == Some(OsStr::new("liquid")) {
-        let msg = t!("rendering_liquid_file").replace(r"\n", "\n") + &format!(" {:?}", file_path);
-        gray(msg);
         let template = liquid::ParserBuilder::with_stdlib()
             .build()
             .expect("should create liquid parser")
             .parse(&String::from_utf8_lossy(tmpl))?;
         let rendered = template.render(data)?;
-        let mut target_file =
-            File::create(file_path.to_string_lossy().trim_end_matches(".liquid"))?;
+        let target_path = file_path.with_extension("");
+        let mut target_file = File::create(&target_path)?;
         target_file.write_all(rendered.as_bytes())?;
+        let msg = t!("rendering_liquid_file").replace(r"\n", "\n") + &format!(" {:?}", file_path);
+        printer::file_written(&target_path, Some(msg));
     } else {
         let mut target_file = File::create(file_path)?;
         target_file.write_all(tmpl)?;
+        printer::file_written(file_path, None);
     }
     Ok(())
 }
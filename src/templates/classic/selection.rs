@@ -1,16 +1,46 @@
+use std::io::IsTerminal;
+
 use anyhow::Result;
-use dialoguer::Select;
 use dialoguer::console::Style;
 use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Password, Select};
 use rust_i18n::t;
+use serde::Deserialize;
+
+use crate::{Project, utils};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default)]
 pub struct Selected {
     pub db_type: DbType,
     pub db_lib: DbLib,
+    pub request_limits: bool,
+    pub http3: bool,
+    pub admin: Admin,
+}
+
+/// The account seeded into a new project, which logs in to its login page.
+#[derive(Debug, Clone)]
+pub struct Admin {
+    pub username: String,
+    pub password: String,
+    /// Whether the password was generated because none was entered, so it must be shown once.
+    pub generated: bool,
 }
 
-pub fn get_selected() -> Result<Option<Selected>> {
+impl Default for Admin {
+    fn default() -> Self {
+        Self {
+            username: "admin".into(),
+            password: utils::random_string(16),
+            generated: true,
+        }
+    }
+}
+
+/// Asks for every choice not given by flags or the user config. Without a terminal to ask on,
+/// the remaining choices take their defaults.
+pub fn get_selected(proj: &Project) -> Result<Option<Selected>> {
+    let interactive = std::io::stdin().is_terminal();
     let theme = ColorfulTheme {
         defaults_style: Style::new().blue(),
         prompt_style: Style::new().green().bold(),
@@ -28,6 +58,96 @@ pub fn get_selected() -> Result<Option<Selected>> {
     //     .default(0)
     //     .items(&selections[..])
     //     .interact()?;
+    let db_lib = match proj.db_lib {
+        Some(db_lib) => db_lib,
+        None if !interactive => DbLib::default(),
+        None => select_db_lib(&theme)?,
+    };
+    let db_type = match (db_lib, proj.db_type) {
+        (DbLib::Mongodb, _) => DbType::Mongodb,
+        // MongoDB is only a database type for the mongodb library.
+        (_, Some(db_type)) if db_type != DbType::Mongodb => db_type,
+        _ if !interactive => DbType::default(),
+        _ => select_db_type(&theme)?,
+    };
+    let confirm = |preset: Option<bool>, prompt: &str, default: bool| -> Result<bool> {
+        match preset {
+            Some(value) => Ok(value),
+            None if !interactive => Ok(default),
+            None => Ok(Confirm::with_theme(&theme)
+                .with_prompt(prompt.replace(r"\n", "\n"))
+                .default(default)
+                .interact()?),
+        }
+    };
+    let request_limits = confirm(proj.request_limits, &t!("select_request_limits"), true)?;
+    let http3 = confirm(proj.http3, &t!("select_http3"), false)?;
+    let admin = select_admin(&theme, proj, interactive)?;
+
+    Ok(Some(Selected {
+        db_type,
+        db_lib,
+        request_limits,
+        http3,
+        admin,
+    }))
+}
+
+/// The admin account from `--admin-username` and the given password, asking for what is missing.
+/// Without a password, one is generated and shown once.
+fn select_admin(theme: &ColorfulTheme, proj: &Project, interactive: bool) -> Result<Admin> {
+    let username = match &proj.admin_username {
+        Some(name) if !is_valid_username(name) => {
+            anyhow::bail!(t!("error_invalid_admin_username"))
+        }
+        Some(name) => name.clone(),
+        None if !interactive => Admin::default().username,
+        None => Input::with_theme(theme)
+            .with_prompt(t!("select_admin_username"))
+            .default("admin".into())
+            .validate_with(|name: &String| {
+                if is_valid_username(name) {
+                    Ok(())
+                } else {
+                    Err(t!("error_invalid_admin_username"))
+                }
+            })
+            .interact_text()?,
+    };
+    let password = match &proj.admin_password {
+        Some(password) => password.clone(),
+        None if !interactive => String::new(),
+        None => Password::with_theme(theme)
+            .with_prompt(t!("select_admin_password"))
+            .with_confirmation(
+                t!("confirm_admin_password"),
+                t!("error_admin_password_mismatch"),
+            )
+            .allow_empty_password(true)
+            .interact()?,
+    };
+    if password.is_empty() {
+        return Ok(Admin {
+            username,
+            ..Default::default()
+        });
+    }
+    Ok(Admin {
+        username,
+        password,
+        generated: false,
+    })
+}
+
+/// Usernames are written into the seed file as they are, so only plain characters are allowed.
+fn is_valid_username(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '@'))
+}
+
+fn select_db_lib(theme: &ColorfulTheme) -> Result<DbLib> {
     let db_libs = &[
         t!("db_lib_sqlx"),
         t!("db_lib_seaorm"),
@@ -36,7 +156,7 @@ pub fn get_selected() -> Result<Option<Selected>> {
         t!("db_lib_mongodb"),
         // "custom",
     ];
-    let db_lib_selection = Select::with_theme(&theme)
+    let db_lib_selection = Select::with_theme(theme)
         .with_prompt(t!("select_db_lib").replace(r"\n", "\n"))
         .default(0)
         .items(&db_libs[..])
@@ -49,19 +169,16 @@ pub fn get_selected() -> Result<Option<Selected>> {
         4 => DbLib::Mongodb,
         _ => anyhow::bail!("Invalid db connection type selection"),
     };
-    if db_lib == DbLib::Mongodb {
-        return Ok(Some(Selected {
-            db_type: DbType::Mongodb,
-            db_lib,
-        }));
-    }
+    Ok(db_lib)
+}
 
+fn select_db_type(theme: &ColorfulTheme) -> Result<DbType> {
     let db_types = &[
         t!("db_type_sqlite"),
         t!("db_type_postgres"),
         t!("db_type_mysql"),
     ];
-    let db_type_selection = Select::with_theme(&theme)
+    let db_type_selection = Select::with_theme(theme)
         .with_prompt(t!("select_db_type").replace(r"\n", "\n"))
         .default(0)
         .items(&db_types[..])
@@ -72,12 +189,14 @@ pub fn get_selected() -> Result<Option<Selected>> {
         2 => DbType::Mysql,
         _ => anyhow::bail!("Invalid db type selection"),
     };
-
-    Ok(Some(Selected { db_type, db_lib }))
+    Ok(db_type)
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, strum::Display)]
+#[derive(Debug, PartialEq, Clone, Copy, Default, strum::Display, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+#[clap(rename_all = "lowercase")]
 pub enum DbType {
+    #[default]
     #[strum(serialize = "sqlite")]
     Sqlite,
     #[strum(serialize = "mysql")]
@@ -88,8 +207,11 @@ pub enum DbType {
     Mongodb,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, strum::Display)]
+#[derive(Debug, PartialEq, Clone, Copy, Default, strum::Display, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+#[clap(rename_all = "lowercase")]
 pub enum DbLib {
+    #[default]
     #[strum(serialize = "sqlx")]
     Sqlx,
     #[strum(serialize = "seaorm")]
@@ -101,3 +223,47 @@ pub enum DbLib {
     #[strum(serialize = "mongodb")]
     Mongodb,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DbLib, DbType, get_selected};
+    use crate::Project;
+
+    fn flagged() -> Project {
+        Project {
+            db_lib: Some(DbLib::Diesel),
+            db_type: Some(DbType::Postgres),
+            request_limits: Some(false),
+            http3: Some(true),
+            admin_username: Some("root".into()),
+            admin_password: Some("s3cret".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fully_flagged_choices_ask_nothing() {
+        let selected = get_selected(&flagged()).unwrap().unwrap();
+        assert_eq!(selected.db_lib, DbLib::Diesel);
+        assert_eq!(selected.db_type, DbType::Postgres);
+        assert!(!selected.request_limits);
+        assert!(selected.http3);
+        assert_eq!(selected.admin.username, "root");
+        assert_eq!(selected.admin.password, "s3cret");
+        assert!(!selected.admin.generated);
+
+        let generated = Project {
+            admin_password: Some(String::new()),
+            ..flagged()
+        };
+        let admin = get_selected(&generated).unwrap().unwrap().admin;
+        assert!(admin.generated);
+        assert_eq!(admin.password.len(), 16);
+
+        let invalid = Project {
+            admin_username: Some("bad name".into()),
+            ..flagged()
+        };
+        assert!(get_selected(&invalid).is_err());
+    }
+}
@@ -1 +1,12 @@
+use serde::Deserialize;
+
 pub mod classic;
+
+/// The project templates `salvo new` can render.
+#[derive(Debug, PartialEq, Clone, Copy, Default, strum::Display, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateKind {
+    #[default]
+    #[strum(serialize = "classic")]
+    Classic,
+}
@@ -5,20 +5,22 @@ mod tests {
 
     use itertools::Itertools;
 
-    use crate::Project;
+    use crate::hooks::{self, Hook, Outcome};
     use crate::templates::classic;
-    use crate::templates::classic::selection::{DbLib, DbType, Selected};
+    use crate::templates::classic::selection::{Admin, DbLib, DbType, Selected};
+    use crate::{Project, generate, git, i18n};
 
-    fn render_project(name: String, lang: &str, user_selected: Selected) -> String {
+    fn render_project(name: String, code_lang: &str, user_selected: Selected) -> String {
         let proj = Project {
             name,
-            lang: lang.to_string(),
+            code_lang: code_lang.to_string(),
+            ..Default::default()
         };
         println!("Testing combination: {:?}", proj.name);
         let path_str = format!("target/{}", proj.name);
         fs::remove_dir_all(&path_str).unwrap_or(());
         let path = Path::new(&path_str);
-        classic::create_files(path, user_selected, &proj)
+        classic::create_files(path, &user_selected, &proj)
             .unwrap_or_else(|e| panic!("project '{}' should render: {e}", proj.name));
         path_str
     }
@@ -27,6 +29,30 @@ mod tests {
         fs::remove_dir_all(path_str).unwrap_or(());
     }
 
+    /// Runs `cargo check` in a generated crate.
+    fn cargo_check(path: &Path) {
+        cargo(path, &["check", "--quiet"]);
+    }
+
+    /// Runs cargo in a generated crate. The crates share a target folder, so their
+    /// dependencies are only built once.
+    fn cargo(path: &Path, args: &[&str]) {
+        let target_dir = std::env::current_dir().unwrap().join("target/generated");
+        let output = std::process::Command::new("cargo")
+            .args(args)
+            .env("CARGO_TARGET_DIR", target_dir)
+            .current_dir(path)
+            .output()
+            .expect("failed to execute process");
+        assert!(
+            output.status.success(),
+            "cargo {} failed in {}: {}",
+            args.join(" "),
+            path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     #[test]
     fn test_write_project_all_combinations() {
         // let db_types = [DbType::Sqlite, DbType::Mysql, DbType::Postgres, DbType::Mssql];
@@ -56,26 +82,21 @@ mod tests {
             let user_selected = Selected {
                 db_type: *db_type,
                 db_lib: *db_lib,
+                request_limits: true,
+                http3: true,
+                ..Default::default()
             };
             let path_str = render_project(
                 format!("test_{:?}_{:?}", db_type, db_lib),
-                "zh",
+                "zh_CN",
                 user_selected,
             );
 
-            let output = std::process::Command::new("cargo")
-                .arg("check")
-                .current_dir(&path_str)
-                .output()
-                .expect("failed to execute process");
-            if !output.status.success() {
-                eprintln!(
-                    "Failed on combination: db_type={:?}, db_lib={:?}",
-                    db_type, db_lib
-                );
-                eprintln!("Output: {:?}", output);
-                panic!();
-            }
+            cargo_check(Path::new(&path_str));
+            cargo(
+                Path::new(&path_str),
+                &["test", "--quiet", "test_openapi_spec_is_up_to_date"],
+            );
 
             cleanup(&path_str);
         }
@@ -96,6 +117,7 @@ mod tests {
                 Selected {
                     db_type,
                     db_lib: DbLib::SeaOrm,
+                    ..Default::default()
                 },
             );
 
@@ -124,6 +146,808 @@ mod tests {
                 "SeaORM migration crate should enable the correct driver feature for {db_type:?}"
             );
 
+            cleanup(&path_str);
+        }
+    }
+    #[test]
+    fn test_request_limits_are_only_generated_when_selected() {
+        for request_limits in [true, false] {
+            let path_str = render_project(
+                format!("test_request_limits_{request_limits}"),
+                "en",
+                Selected {
+                    request_limits,
+                    ..Default::default()
+                },
+            );
+
+            let limiter = fs::read_to_string(format!("{path_str}/src/hoops/limiter.rs"));
+            assert_eq!(limiter.is_ok(), request_limits);
+            if let Ok(limiter) = limiter {
+                // Login matches usernames case-sensitively, so the rate limit key does too.
+                assert!(limiter.contains(r#"Some(format!("user:{username}"))"#));
+            }
+            assert_eq!(
+                Path::new(&format!("{path_str}/src/config/limit_config.rs")).exists(),
+                request_limits
+            );
+            let routers = fs::read_to_string(format!("{path_str}/src/routers/mod.rs"))
+                .expect("routers should exist");
+            assert_eq!(routers.contains("login_ip_limiter"), request_limits);
+            assert_eq!(routers.contains("login_username_limiter"), request_limits);
+            let config = fs::read_to_string(format!("{path_str}/config.toml"))
+                .expect("config.toml should exist");
+            assert_eq!(config.contains("[limit]"), request_limits);
+            let cargo = fs::read_to_string(format!("{path_str}/Cargo.toml"))
+                .expect("Cargo.toml should exist");
+            assert_eq!(cargo.contains("\"rate-limiter\""), request_limits);
+
+            cleanup(&path_str);
+        }
+    }
+    #[test]
+    fn test_ignore_files_follow_the_selections() {
+        let sqlite = render_project(
+            "test_ignore_files_sqlite".into(),
+            "en",
+            Selected {
+                db_lib: DbLib::SeaOrm,
+                db_type: DbType::Sqlite,
+                ..Default::default()
+            },
+        );
+        let gitignore =
+            fs::read_to_string(format!("{sqlite}/.gitignore")).expect(".gitignore should exist");
+        assert_eq!(
+            gitignore,
+            "/target\n/migration/target\n/logs/\n/.env\n/certs/key.pem\n/data/*.sqlite*\n"
+        );
+        let dockerignore = fs::read_to_string(format!("{sqlite}/.dockerignore"))
+            .expect(".dockerignore should exist");
+        assert_eq!(dockerignore, format!("/.git\n{gitignore}"));
+        for file in [".editorconfig", "rustfmt.toml", "clippy.toml"] {
+            assert!(Path::new(&format!("{sqlite}/{file}")).exists(), "{file}");
+        }
+        cleanup(&sqlite);
+
+        let mongodb = render_project(
+            "test_ignore_files_mongodb".into(),
+            "en",
+            Selected {
+                db_lib: DbLib::Mongodb,
+                db_type: DbType::Mongodb,
+                ..Default::default()
+            },
+        );
+        let gitignore =
+            fs::read_to_string(format!("{mongodb}/.gitignore")).expect(".gitignore should exist");
+        assert_eq!(gitignore, "/target\n/logs/\n/.env\n/certs/key.pem\n");
+        let log_config = fs::read_to_string(format!("{mongodb}/src/config/log_config.rs"))
+            .expect("log_config.rs should exist");
+        assert!(log_config.contains("\"./logs\".into()"));
+        cleanup(&mongodb);
+    }
+    #[test]
+    fn test_initial_commit_leaves_the_secrets_out() {
+        let path_str = render_project(
+            "test_initial_commit".into(),
+            "en",
+            Selected {
+                db_type: DbType::Postgres,
+                ..Default::default()
+            },
+        );
+        let path = Path::new(&path_str);
+        git::init_repository(path).unwrap();
+        let proj = Project {
+            hooks: vec![Hook::Commit],
+            git_author: Some(("Ada".into(), "ada@example.com".into())),
+            ..Default::default()
+        };
+        assert_eq!(hooks::run(path, &proj), [(Hook::Commit, Outcome::Done)]);
+        let repo = git2::Repository::open(path).unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_path(Path::new("config.toml")).is_ok());
+        for file in classic::SECRET_FILES {
+            assert!(
+                tree.get_path(Path::new(file)).is_err(),
+                "{file} was committed"
+            );
+        }
+
+        // Secret files that git would pick up stop the commit.
+        fs::write(path.join(".gitignore"), "/target\n").unwrap();
+        let outcomes = hooks::run(path, &proj);
+        assert!(
+            matches!(&outcomes[0].1, Outcome::Skipped(reason) if reason.contains(".env")),
+            "{outcomes:?}"
+        );
+
+        cleanup(&path_str);
+    }
+    #[test]
+    fn test_tls_config_supports_static_and_acme_modes() {
+        let path_str = render_project("test_tls_modes".to_string(), "en", Selected::default());
+
+        let main =
+            fs::read_to_string(format!("{path_str}/src/main.rs")).expect("main.rs should exist");
+        assert!(main.contains("tls.mode == TlsMode::Acme"));
+        assert!(main.contains(".http01_challenge(&mut router)"));
+        assert!(main.contains(".directory(directory_name, directory_url)"));
+        assert!(main.contains("https://{domain}/scalar"));
+        assert!(!main.contains("acme_support"));
+        assert!(main.contains("RustlsConfig::new("));
+        let config = fs::read_to_string(format!("{path_str}/config.toml"))
+            .expect("config.toml should exist");
+        assert!(config.contains("# mode = \"acme\""));
+        assert!(config.contains("# directory_url = "));
+        let config_rs = fs::read_to_string(format!("{path_str}/src/config/mod.rs"))
+            .expect("config/mod.rs should exist");
+        assert!(config_rs.contains("pub fn acme_directory(&self) -> (&str, &str) {"));
+        cargo(Path::new(&path_str), &["test", "--quiet", "acme_directory"]);
+        let cargo =
+            fs::read_to_string(format!("{path_str}/Cargo.toml")).expect("Cargo.toml should exist");
+        assert!(cargo.contains("\"acme\""));
+
+        cleanup(&path_str);
+    }
+    #[test]
+    fn test_credentials_are_random_per_project_and_consistent() {
+        let mut secrets = Vec::new();
+        for index in 0..2 {
+            let path_str = render_project(
+                format!("test_credentials_{index}"),
+                "en",
+                Selected {
+                    db_type: DbType::Postgres,
+                    ..Default::default()
+                },
+            );
+
+            let env = fs::read_to_string(format!("{path_str}/.env")).expect(".env should exist");
+            let secret = env
+                .lines()
+                .find_map(|line| line.strip_prefix("JWT_SECRET="))
+                .expect("jwt secret should be set")
+                .to_string();
+            assert_eq!(secret.len(), 64);
+            let password = env
+                .split("postgres://postgres:")
+                .nth(1)
+                .and_then(|rest| rest.split('@').next())
+                .expect("database password should be set")
+                .to_string();
+            // Only the ignored secret files hold the generated secrets.
+            for entry in walkdir::WalkDir::new(&path_str) {
+                let entry = entry.unwrap();
+                let Ok(content) = fs::read_to_string(entry.path()) else {
+                    continue;
+                };
+                let relative = entry.path().strip_prefix(&path_str).unwrap();
+                if content.contains(&secret) || content.contains(&password) {
+                    assert!(
+                        classic::SECRET_FILES.contains(&relative.to_str().unwrap()),
+                        "{} holds a secret",
+                        relative.display()
+                    );
+                }
+            }
+            let config = fs::read_to_string(format!("{path_str}/config.toml"))
+                .expect("config.toml should exist");
+            assert!(!config.contains("yoursecret"));
+            assert!(!config.contains("postgres:root@"));
+            let readme = fs::read_to_string(format!("{path_str}/README.md"))
+                .expect("README.md should exist");
+            assert!(readme.contains("TEST_DATABASE_URL=postgres://<user>:<password>@"));
+            secrets.push((secret, password));
+
+            cleanup(&path_str);
+        }
+        assert_ne!(secrets[0].0, secrets[1].0);
+        assert_ne!(secrets[0].1, secrets[1].1);
+    }
+    #[test]
+    fn test_generated_text_follows_the_code_language() {
+        for (code_lang, expected) in [("en", "//Run the project"), ("zh_CN", "//运行项目")] {
+            let path_str = render_project(
+                format!("test_code_lang_{code_lang}"),
+                code_lang,
+                Selected::default(),
+            );
+            let readme = fs::read_to_string(Path::new(&path_str).join("README.md")).unwrap();
+            assert!(readme.contains(expected), "{code_lang} README: {readme}");
+            assert!(
+                readme.contains("Rust 1.94"),
+                "the tip names the rust-version"
+            );
+            cleanup(&path_str);
+        }
+        assert!(i18n::parse_code_lang("zh").is_ok_and(|lang| lang == "zh_CN"));
+        assert!(i18n::parse_code_lang("de_DE").is_ok_and(|lang| lang == "de"));
+        assert!(i18n::parse_code_lang("xx").is_err());
+    }
+
+    #[test]
+    fn test_configured_author_is_written_to_cargo_toml() {
+        for author in [Some("Ada \"A\" <ada@example.com>"), None] {
+            let proj = Project {
+                name: format!("test_author_{}", author.is_some()),
+                code_lang: "en".to_string(),
+                author: author.map(str::to_owned),
+                ..Default::default()
+            };
+            let path_str = format!("target/{}", proj.name);
+            fs::remove_dir_all(&path_str).unwrap_or(());
+            classic::create_files(Path::new(&path_str), &Selected::default(), &proj).unwrap();
+
+            let manifest = fs::read_to_string(format!("{path_str}/Cargo.toml")).unwrap();
+            let manifest: toml::Table = manifest.parse().expect("Cargo.toml should parse");
+            let authors = manifest["package"]
+                .get("authors")
+                .and_then(|a| a.as_array());
+            let author_in_manifest = authors.map(|a| a[0].as_str().unwrap());
+            assert_eq!(author_in_manifest, author);
+            cleanup(&path_str);
+        }
+    }
+
+    #[test]
+    fn test_http3_listener_is_only_generated_when_selected() {
+        for http3 in [true, false] {
+            let path_str = render_project(
+                format!("test_http3_{http3}"),
+                "en",
+                Selected {
+                    http3,
+                    ..Default::default()
+                },
+            );
+
+            let main = fs::read_to_string(format!("{path_str}/src/main.rs"))
+                .expect("main.rs should exist");
+            assert_eq!(main.contains("QuinnListener::new("), http3);
+            assert_eq!(main.contains(".quinn(listen_addr.as_str())"), http3);
+            let config = fs::read_to_string(format!("{path_str}/config.toml"))
+                .expect("config.toml should exist");
+            assert_eq!(config.contains("# http3 = true"), http3);
+            let cargo = fs::read_to_string(format!("{path_str}/Cargo.toml"))
+                .expect("Cargo.toml should exist");
+            assert_eq!(cargo.contains("\"quinn\""), http3);
+
+            cleanup(&path_str);
+        }
+    }
+    #[test]
+    fn test_openapi_export_mode_and_drift_test_are_generated() {
+        let path_str = render_project("test_openapi_export".to_string(), "en", Selected::default());
+
+        let main =
+            fs::read_to_string(format!("{path_str}/src/main.rs")).expect("main.rs should exist");
+        assert!(main.contains("if let Some(path) = export_openapi_path() {"));
+        let routers = fs::read_to_string(format!("{path_str}/src/routers/mod.rs"))
+            .expect("routers/mod.rs should exist");
+        assert!(routers.contains("pub fn export_openapi(path: &Path)"));
+        assert!(routers.contains("fn test_openapi_spec_is_up_to_date()"));
+        let readme =
+            fs::read_to_string(format!("{path_str}/README.md")).expect("README.md should exist");
+        assert!(readme.contains("cargo run -- --export-openapi openapi.json"));
+
+        cleanup(&path_str);
+    }
+    #[test]
+    fn test_generated_openapi_spec_matches_the_routes() {
+        for (db_lib, db_type) in [
+            (DbLib::Sqlx, DbType::Sqlite),
+            (DbLib::Rbatis, DbType::Sqlite),
+            (DbLib::Mongodb, DbType::Mongodb),
+        ] {
+            let path_str = render_project(
+                format!("test-openapi-spec-{db_lib}"),
+                "en",
+                Selected {
+                    db_lib,
+                    db_type,
+                    ..Default::default()
+                },
+            );
+            let spec = fs::read_to_string(format!("{path_str}/openapi.json"))
+                .expect("openapi.json should exist");
+            let crate_name = format!("test_openapi_spec_{db_lib}");
+            assert!(spec.contains(&format!("\"{crate_name}.routers.auth.post_login\"")));
+            // The generated drift test checks the shipped spec against the routes.
+            cargo(
+                Path::new(&path_str),
+                &["test", "--quiet", "test_openapi_spec_is_up_to_date"],
+            );
+
+            cleanup(&path_str);
+        }
+    }
+
+    #[test]
+    fn test_generate_schema_writes_crud_code_for_each_db_lib() {
+        for db_lib in [DbLib::Sqlx, DbLib::SeaOrm, DbLib::Diesel, DbLib::Rbatis] {
+            let path_str = render_project(
+                format!("test_generate_schema_{db_lib}"),
+                "en",
+                Selected {
+                    db_lib,
+                    ..Default::default()
+                },
+            );
+            let path = Path::new(&path_str);
+            let db_path = path.join("schema.sqlite");
+            rusqlite::Connection::open(&db_path)
+                .unwrap()
+                .execute_batch(
+                    "CREATE TABLE users (id TEXT PRIMARY KEY, username TEXT NOT NULL);
+                    CREATE TABLE blog_posts (id INTEGER PRIMARY KEY, title TEXT NOT NULL, body TEXT, published BOOLEAN NOT NULL);
+                    CREATE TABLE post_tags (post_id INTEGER, tag TEXT, PRIMARY KEY (post_id, tag));",
+                )
+                .unwrap();
+
+            // Projects created before the template shipped the pagination module get it added.
+            fs::remove_file(path.join("src/pagination.rs")).unwrap();
+            let main = fs::read_to_string(path.join("src/main.rs")).unwrap();
+            fs::write(
+                path.join("src/main.rs"),
+                main.replace("mod pagination;\n", ""),
+            )
+            .unwrap();
+
+            generate::schema::generate(path, &db_path.to_string_lossy(), &[], false)
+                .unwrap_or_else(|e| panic!("{db_lib} code should be generated: {e}"));
+
+            assert!(path.join("src/pagination.rs").exists());
+            let main = fs::read_to_string(path.join("src/main.rs")).unwrap();
+            assert!(main.contains("mod models;\nmod pagination;\n"));
+
+            let model = fs::read_to_string(path.join("src/models/blog_posts.rs")).unwrap();
+            assert!(model.contains("pub struct BlogPost {"));
+            assert!(model.contains("pub body: Option<String>,"));
+            let router = fs::read_to_string(path.join("src/routers/blog_posts.rs")).unwrap();
+            assert!(router.contains("pub async fn list_blog_posts("));
+            assert!(router.contains("blog_post_id: PathParam<i64>"));
+            assert!(router.contains("(query: PageQuery) -> JsonResult<Page<BlogPost>>"));
+            assert!(router.contains("query.cursor::<i64>()?"));
+            assert!(!path.join("src/routers/post_tags.rs").exists());
+            assert!(!path.join("src/routers/users.rs").exists());
+            let routers = fs::read_to_string(path.join("src/routers/mod.rs")).unwrap();
+            assert!(routers.contains("mod blog_posts;"));
+            assert!(routers.contains(".push(blog_posts::router())"));
+            match db_lib {
+                DbLib::SeaOrm => {
+                    let prelude = fs::read_to_string(path.join("src/entities/prelude.rs")).unwrap();
+                    assert!(prelude.contains("pub use super::blog_posts::Entity as BlogPosts;"));
+                }
+                DbLib::Rbatis => {
+                    assert!(model.contains("#[serde(deserialize_with = \"bool_from_int\")]"));
+                }
+                DbLib::Diesel => {
+                    let schema = fs::read_to_string(path.join("src/schema.rs")).unwrap();
+                    assert!(schema.contains("blog_posts (id) {"));
+                    assert!(schema.contains("body -> Nullable<Text>,"));
+                }
+                _ => {}
+            }
+            cargo_check(path);
+
+            // A second run keeps the code that is already there.
+            fs::write(path.join("src/routers/blog_posts.rs"), "// edited").unwrap();
+            generate::schema::generate(path, &db_path.to_string_lossy(), &[], false).unwrap();
+            assert_eq!(
+                fs::read_to_string(path.join("src/routers/blog_posts.rs")).unwrap(),
+                "// edited"
+            );
+            let routers = fs::read_to_string(path.join("src/routers/mod.rs")).unwrap();
+            assert_eq!(routers.matches("blog_posts::router()").count(), 1);
+
+            cleanup(&path_str);
+        }
+    }
+
+    #[test]
+    fn test_generate_openapi_keeps_handler_bodies_on_rerun() {
+        let path_str = render_project(
+            "test_generate_openapi".to_string(),
+            "en",
+            Selected::default(),
+        );
+        let path = Path::new(&path_str);
+        let spec_path = path.join("openapi.yaml");
+        let spec = r#"openapi: 3.0.3
+info: {title: Pets, version: 1.0.0}
+security: [{bearerAuth: []}]
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      tags: [pets]
+      security: []
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, format: int32}}
+      responses:
+        '200':
+          description: pets
+          content:
+            application/json:
+              schema: {type: array, items: {$ref: '#/components/schemas/Pet'}}
+    post:
+      operationId: createPet
+      tags: [pets]
+      parameters:
+        - {name: dry-run, in: query, schema: {type: boolean}}
+        - {name: owner, in: query, required: true, schema: {type: string}}
+      requestBody:
+        content:
+          application/json:
+            schema:
+              allOf:
+                - {$ref: '#/components/schemas/NewPet'}
+                - type: object
+                  properties:
+                    nickname: {type: string, nullable: true}
+      responses:
+        '201':
+          description: created
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Pet'}
+  /pets/{petId}:
+    get:
+      operationId: showPetById
+      tags: [pets]
+      parameters:
+        - {name: petId, in: path, required: true, schema: {type: integer}}
+      responses:
+        '200':
+          description: pet
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Pet'}
+components:
+  schemas:
+    NewPet:
+      type: object
+      required: [name]
+      properties:
+        name: {type: string}
+        tag: {type: string, nullable: true}
+    Pet:
+      allOf:
+        - {$ref: '#/components/schemas/NewPet'}
+        - type: object
+          required: [id]
+          properties:
+            id: {type: integer}
+            birthDate: {type: string}
+            owner:
+              type: object
+              properties:
+                email: {type: string}
+"#;
+        fs::write(&spec_path, spec).unwrap();
+        generate::openapi::generate(path, &spec_path).expect("code should be generated");
+
+        let models = fs::read_to_string(path.join("src/models/openapi.rs")).unwrap();
+        assert!(models.contains("#[salvo(schema(name = \"Pet\"))]"));
+        assert!(models.contains("pub birth_date: Option<String>,"));
+        let router_path = path.join("src/routers/pets.rs");
+        let router = fs::read_to_string(&router_path).unwrap();
+        assert!(router.contains(
+            "pub async fn list_pets(limit: QueryParam<i32, false>) -> JsonResult<Vec<Pet>> {"
+        ));
+        assert!(router.contains("Router::with_path(\"pets/{pet_id}\")"));
+        assert!(
+            router.contains(
+                "pub async fn show_pet_by_id(pet_id: PathParam<i64>) -> JsonResult<Pet> {"
+            )
+        );
+        assert!(router.contains("query: CreatePetQuery, body: JsonBody<CreatePetBody>"));
+        assert!(models.contains("pub struct CreatePetQuery {"));
+        assert!(models.contains("pub nickname: Option<String>,"));
+        let routers = fs::read_to_string(path.join("src/routers/mod.rs")).unwrap();
+        assert!(routers.contains("mod pets;"));
+        assert!(routers.contains(".push(pets::router())"));
+        cargo_check(path);
+
+        // Hand-written bodies survive a re-run that adds an operation.
+        fs::write(
+            &router_path,
+            router.replace(
+                "Err(StatusError::not_implemented()\n        .brief(\"`list_pets` is not implemented yet\")\n        .into())",
+                "json_ok(vec![])",
+            ),
+        )
+        .unwrap();
+        let spec = spec.replace(
+            "components:",
+            "    delete:\n      operationId: deletePet\n      tags: [pets]\n      responses:\n        '204': {description: deleted}\ncomponents:",
+        );
+        fs::write(&spec_path, spec).unwrap();
+        generate::openapi::generate(path, &spec_path).unwrap();
+
+        let router = fs::read_to_string(&router_path).unwrap();
+        assert!(router.contains("json_ok(vec![])"));
+        assert_eq!(router.matches("pub async fn list_pets(").count(), 1);
+        assert!(router.contains(".delete(delete_pet)"));
+        assert!(router.contains("pub async fn delete_pet() -> EmptyResult {"));
+        let routers = fs::read_to_string(path.join("src/routers/mod.rs")).unwrap();
+        assert_eq!(routers.matches("pets::router()").count(), 1);
+
+        cleanup(&path_str);
+    }
+
+    #[tokio::test]
+    async fn test_generate_client_from_spec_file() {
+        let path_str = render_project(
+            "test_generate_client".to_string(),
+            "en",
+            Selected::default(),
+        );
+        let path = Path::new(&path_str);
+        let spec_path = path.join("openapi.yaml");
+        let spec = r#"openapi: 3.1.0
+info: {title: Pets, version: 1.0.0}
+paths:
+  /api/login:
+    post:
+      operationId: app.routers.auth.post_login
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: '#/components/schemas/app.routers.auth.LoginInData'}
+      responses:
+        '200':
+          description: token
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/app.routers.auth.LoginOutData'}
+  /api/pets:
+    get:
+      operationId: listPets
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, format: int32}}
+        - {name: tags, in: query, schema: {type: array, items: {type: string}}}
+      responses:
+        '200':
+          description: pets
+          content:
+            application/json:
+              schema: {type: array, items: {$ref: '#/components/schemas/Pet'}}
+  /api/pets/{petId}:
+    delete:
+      operationId: deletePet
+      parameters:
+        - {name: petId, in: path, required: true, schema: {type: string}}
+      responses:
+        '204': {description: deleted}
+components:
+  schemas:
+    app.routers.auth.LoginInData:
+      type: object
+      required: [username, password]
+      properties:
+        username: {type: string}
+        password: {type: string}
+    app.routers.auth.LoginOutData:
+      type: object
+      required: [token]
+      properties:
+        token: {type: string}
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id: {type: integer}
+        name: {type: string}
+"#;
+        fs::write(&spec_path, spec).unwrap();
+        generate::client::generate(path, spec_path.to_str(), None)
+            .await
+            .expect("client should be generated");
+
+        let client = path.join("client");
+        let manifest = fs::read_to_string(client.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"test_generate_client-client\""));
+        let models = fs::read_to_string(client.join("src/models.rs")).unwrap();
+        assert!(models.contains("pub struct LoginOutData {"));
+        assert!(!models.contains("ToSchema"));
+        let lib = fs::read_to_string(client.join("src/lib.rs")).unwrap();
+        assert!(lib.contains(
+            "pub async fn post_login(&self, body: &LoginInData) -> Result<LoginOutData> {"
+        ));
+        assert!(lib.contains("self.set_token(out.token.clone());"));
+        assert!(lib.contains(
+            "pub async fn list_pets(&self, limit: Option<i32>, tags: Option<&[String]>) -> Result<Vec<Pet>> {"
+        ));
+        assert!(lib.contains("for value in tags.unwrap_or_default() {"));
+        assert!(lib.contains("pub async fn delete_pet(&self, pet_id: &str) -> Result<()> {"));
+        assert!(lib.contains("&format!(\"/api/pets/{}\", segment(pet_id))"));
+        cargo_check(&client);
+
+        // Files changed by hand are not overwritten.
+        fs::write(client.join("src/lib.rs"), "mod models;\n").unwrap();
+        assert!(
+            generate::client::generate(path, spec_path.to_str(), None)
+                .await
+                .is_err()
+        );
+
+        cleanup(&path_str);
+    }
+
+    #[test]
+    fn test_admin_account_is_seeded_instead_of_a_fixed_user() {
+        for (db_lib, db_type) in [
+            (DbLib::Sqlx, DbType::Sqlite),
+            (DbLib::SeaOrm, DbType::Sqlite),
+            (DbLib::Diesel, DbType::Sqlite),
+            (DbLib::Rbatis, DbType::Sqlite),
+            (DbLib::Sqlx, DbType::Postgres),
+            (DbLib::Mongodb, DbType::Mongodb),
+        ] {
+            let path_str = render_project(
+                format!("test_seed_{db_lib}_{db_type}"),
+                "en",
+                Selected {
+                    db_lib,
+                    db_type,
+                    admin: Admin {
+                        username: "root@example.com".into(),
+                        password: "s3cret".into(),
+                        generated: false,
+                    },
+                    ..Default::default()
+                },
+            );
+            let path = Path::new(&path_str);
+            for entry in walkdir::WalkDir::new(path).into_iter().flatten() {
+                if let Ok(content) = fs::read_to_string(entry.path()) {
+                    assert!(
+                        !content.contains("zhangsan"),
+                        "{} should not ship a fixed user",
+                        entry.path().display()
+                    );
+                }
+            }
+
+            let seed: toml::Table =
+                toml::from_str(&fs::read_to_string(path.join("seeds/users.toml")).unwrap())
+                    .unwrap();
+            let row = &seed["rows"].as_array().unwrap()[0];
+            assert_eq!(row["username"].as_str(), Some("root@example.com"));
+            let hash = row["password"].as_str().unwrap();
+            argon2::PasswordHash::new(hash)
+                .unwrap()
+                .verify_password(&[&argon2::Argon2::default()], "s3cret")
+                .expect("the seeded hash should verify the chosen password");
+            if db_lib == DbLib::Mongodb {
+                assert_eq!(row["_id"]["$oid"].as_str().map(str::len), Some(24));
+            }
+
+            if db_type == DbType::Sqlite {
+                let users = rusqlite::Connection::open(path.join(format!("data/{db_lib}.sqlite")))
+                    .unwrap()
+                    .query_row("SELECT username, password FROM users", [], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })
+                    .unwrap();
+                assert_eq!(users, ("root@example.com".to_owned(), hash.to_owned()));
+            }
+
+            cleanup(&path_str);
+        }
+    }
+
+    #[test]
+    fn test_sqlite_starter_database_is_built_from_migrations() {
+        for db_lib in [DbLib::Sqlx, DbLib::SeaOrm, DbLib::Diesel, DbLib::Rbatis] {
+            let path_str = render_project(
+                format!("test_starter_{db_lib}"),
+                "en",
+                Selected {
+                    db_lib,
+                    db_type: DbType::Sqlite,
+                    ..Default::default()
+                },
+            );
+            let path = Path::new(&path_str);
+            let conn =
+                rusqlite::Connection::open(path.join(format!("data/{db_lib}.sqlite"))).unwrap();
+            let strings = |sql: &str| {
+                conn.prepare(sql)
+                    .unwrap()
+                    .query_map([], |row| row.get::<_, String>(0))
+                    .unwrap()
+                    .collect::<rusqlite::Result<Vec<_>>>()
+                    .unwrap()
+            };
+            let names = |dir: &str| {
+                let mut names = fs::read_dir(path.join(dir))
+                    .unwrap()
+                    .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            };
+            let (recorded, expected) = match db_lib {
+                DbLib::Sqlx => (
+                    strings(
+                        "SELECT version || '_' || description || '.sql' FROM _sqlx_migrations ORDER BY version",
+                    ),
+                    names("migrations"),
+                ),
+                DbLib::SeaOrm => (
+                    strings("SELECT version || '.rs' FROM seaql_migrations ORDER BY version"),
+                    names("migration/src")
+                        .into_iter()
+                        .filter(|name| name.starts_with('m') && name != "main.rs")
+                        .collect(),
+                ),
+                DbLib::Diesel => (
+                    strings("SELECT version FROM __diesel_schema_migrations ORDER BY version"),
+                    names("migrations")
+                        .into_iter()
+                        .filter_map(|name| Some(name.split_once('_')?.0.replace('-', "")))
+                        .collect(),
+                ),
+                _ => (Vec::new(), Vec::new()),
+            };
+            assert_eq!(recorded, expected, "{db_lib} should record its migrations");
+            assert_eq!(
+                strings("SELECT username FROM users"),
+                ["admin"],
+                "{db_lib} should create and seed the users table"
+            );
+
+            cleanup(&path_str);
+        }
+    }
+
+    #[test]
+    fn test_api_tests_run_against_a_throwaway_database() {
+        for (db_lib, db_type) in [
+            (DbLib::Sqlx, DbType::Sqlite),
+            (DbLib::SeaOrm, DbType::Sqlite),
+            (DbLib::Diesel, DbType::Postgres),
+            (DbLib::Mongodb, DbType::Mongodb),
+        ] {
+            let path_str = render_project(
+                format!("test_api_tests_{db_lib}_{db_type}"),
+                "en",
+                Selected {
+                    db_lib,
+                    db_type,
+                    ..Default::default()
+                },
+            );
+            let path = Path::new(&path_str);
+            let tests = fs::read_to_string(path.join("src/api_tests.rs")).unwrap();
+            assert!(tests.contains("async fn test_manage_users()"));
+            assert!(tests.contains("async fn test_list_users_pages_and_sorts()"));
+            let users = fs::read_to_string(path.join("src/routers/user.rs")).unwrap();
+            assert!(users.contains("query: PageQuery,"));
+            let support = fs::read_to_string(path.join("src/test_support.rs")).unwrap();
+            assert!(support.contains("pub struct TestDb"));
+            assert_eq!(
+                support.contains("begin_test_transaction"),
+                db_lib == DbLib::Diesel && db_type != DbType::Sqlite
+            );
+            assert_eq!(
+                tests.contains("#[ignore"),
+                db_type != DbType::Sqlite,
+                "only {db_lib} on sqlite should run without TEST_DATABASE_URL"
+            );
+            let manifest = fs::read_to_string(path.join("Cargo.toml")).unwrap();
+            // salvo already enables a jsonwebtoken crypto provider, a second one panics at runtime.
+            assert!(manifest.contains("jsonwebtoken = \"10\""));
+            assert!(!manifest.contains("rust_crypto"));
+            assert_eq!(
+                manifest.contains("migration = { path = \"migration\" }"),
+                db_lib == DbLib::SeaOrm
+            );
+
             cleanup(&path_str);
         }
     }
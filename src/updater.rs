@@ -1,18 +1,30 @@
-use std::env;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs};
 
+use anyhow::Context;
 use rust_i18n::t;
 use semver::Version;
-use serde::Deserialize;
-use serde_json::from_str;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, json};
 use tokio::time::timeout;
 
-use crate::printer::{green, orange, success, warning};
+use crate::config::{Config, UpdateCheck};
+use crate::printer::{self, gray, green, orange, success, warning};
+
+const DEFAULT_REGISTRY: &str = "https://crates.io";
+/// How long a successful check is reused before asking the registry again.
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Failed checks are retried sooner, but not on every run when offline.
+const FAILED_CHECK_TTL: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Debug, Deserialize)]
 struct CratesResponse {
     #[serde(rename = "crate")]
     package: CratesPackage,
+    #[serde(default)]
+    versions: Vec<CrateVersion>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,22 +32,138 @@ struct CratesPackage {
     max_version: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct CrateVersion {
+    num: String,
+    created_at: Option<String>,
+    rust_version: Option<String>,
+}
+
+/// The newest release of a crate on the registry.
+#[derive(Debug, PartialEq, Eq)]
+struct Release {
+    version: Version,
+    /// When it was published, as the registry reports it.
+    published: Option<String>,
+    rust_version: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum UpdateStatus {
     UpToDate,
     UpdateAvailable(Version),
 }
 
-async fn get_latest_version(crate_name: &str) -> Result<Version, Box<dyn std::error::Error>> {
-    let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
-    let client = reqwest::Client::builder()
-        .user_agent("salvo-cli update checker")
-        .build()?;
+/// Where update checks look, set by `registry`, `proxy` and `update_check` in the user config.
+#[derive(Debug, Clone)]
+pub struct Source {
+    /// A registry serving the crates.io API, such as a company mirror.
+    registry: String,
+    proxy: Option<String>,
+    /// `update_check = "always"` asks the registry on every run.
+    use_cache: bool,
+}
+
+impl Source {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            registry: config
+                .registry
+                .clone()
+                .unwrap_or_else(|| DEFAULT_REGISTRY.to_owned()),
+            proxy: config.proxy.clone(),
+            use_cache: config.update_check != Some(UpdateCheck::Always),
+        }
+    }
 
-    let resp = client.get(&url).send().await?.error_for_status()?;
+    fn client(&self) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().user_agent("salvo-cli update checker");
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        builder.build()
+    }
+}
+
+async fn get_latest_release(
+    source: &Source,
+    crate_name: &str,
+) -> Result<Release, Box<dyn std::error::Error>> {
+    let url = format!(
+        "{}/api/v1/crates/{}",
+        source.registry.trim_end_matches('/'),
+        crate_name
+    );
+    let resp = source
+        .client()?
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?;
     let body = resp.text().await?;
     let crate_response: CratesResponse = from_str(&body)?;
-    Ok(Version::parse(&crate_response.package.max_version)?)
+    let max_version = crate_response.package.max_version;
+    let details = crate_response
+        .versions
+        .into_iter()
+        .find(|version| version.num == max_version);
+    Ok(Release {
+        version: Version::parse(&max_version)?,
+        published: details.as_ref().and_then(|v| v.created_at.clone()),
+        rust_version: details.and_then(|v| v.rust_version),
+    })
+}
+
+/// The result of the last check, kept between runs.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedCheck {
+    registry: String,
+    /// Seconds since the Unix epoch.
+    checked_at: u64,
+    /// `None` when the check failed.
+    latest_version: Option<String>,
+}
+
+impl CachedCheck {
+    /// Whether the check still stands for `registry` at `now`.
+    fn is_fresh(&self, registry: &str, now: u64) -> bool {
+        let ttl = match self.latest_version {
+            Some(_) => CACHE_TTL,
+            None => FAILED_CHECK_TTL,
+        };
+        self.registry == registry && now >= self.checked_at && now - self.checked_at < ttl.as_secs()
+    }
+}
+
+/// `$XDG_CACHE_HOME/salvo/update-check.json`, falling back to `~/.cache`, or `%LOCALAPPDATA%` on Windows.
+fn cache_path() -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    let dir = var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".cache")))
+        .or_else(|| var("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(dir.join("salvo").join("update-check.json"))
+}
+
+fn read_cache(path: &Path) -> Option<CachedCheck> {
+    from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Saves the check, a cache that cannot be written only costs a request on the next run.
+fn write_cache(path: &Path, check: &CachedCheck) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string(check) {
+        let _ = fs::write(path, content);
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
 }
 
 fn should_skip_update_check() -> bool {
@@ -58,33 +186,66 @@ fn resolve_update_status(
     }
 }
 
-pub async fn check_for_updates() {
+fn print_update_status(latest_version: Version) {
+    let current_version = env!("CARGO_PKG_VERSION");
+    match resolve_update_status(current_version, latest_version) {
+        Ok(UpdateStatus::UpdateAvailable(latest_version)) => {
+            orange(t!("new_version_available", latest_version = latest_version));
+            orange(t!(
+                "currently_using_version",
+                current_version = current_version
+            ));
+            orange(t!("consider_updating"));
+        }
+        Ok(UpdateStatus::UpToDate) => {
+            green(t!("current_version_up_to_date"));
+        }
+        Err(e) => {
+            warning(format!("{},{}", t!("unable_to_verify_updates"), e));
+        }
+    }
+}
+
+pub async fn check_for_updates(source: &Source) {
     if should_skip_update_check() {
         return;
     }
+    let cache_path = cache_path();
+    let cached = cache_path
+        .as_deref()
+        .filter(|_| source.use_cache)
+        .and_then(read_cache)
+        .filter(|cached| cached.is_fresh(&source.registry, now()));
+    if let Some(cached) = cached {
+        log::debug!("using the update check of {}", cached.checked_at);
+        // A failed check stays quiet until it is retried.
+        if let Some(version) = cached.latest_version.and_then(|v| Version::parse(&v).ok()) {
+            print_update_status(version);
+        }
+        return;
+    }
+
     success(t!("checking_for_updates"));
-    let result = timeout(Duration::from_secs(3), get_latest_version("salvo-cli")).await;
+    let result = timeout(
+        Duration::from_secs(3),
+        get_latest_release(source, "salvo-cli"),
+    )
+    .await;
+    let latest_version = match &result {
+        Ok(Ok(release)) => Some(release.version.to_string()),
+        _ => None,
+    };
+    if let Some(path) = &cache_path {
+        let check = CachedCheck {
+            registry: source.registry.clone(),
+            checked_at: now(),
+            latest_version,
+        };
+        write_cache(path, &check);
+    }
 
     match result {
-        Ok(Ok(latest_version)) => {
-            let current_version = env!("CARGO_PKG_VERSION");
-            match resolve_update_status(current_version, latest_version) {
-                Ok(UpdateStatus::UpdateAvailable(latest_version)) => {
-                    orange(t!("new_version_available", latest_version = latest_version));
-                    orange(t!(
-                        "currently_using_version",
-                        current_version = current_version
-                    ));
-                    orange(t!("consider_updating"));
-                }
-                Ok(UpdateStatus::UpToDate) => {
-                    green(t!("current_version_up_to_date"));
-                }
-                Err(e) => {
-                    warning(format!("{},{}", t!("unable_to_verify_updates"), e));
-                }
-            }
-        }
+        Ok(Ok(release)) => print_update_status(release.version),
         Ok(Err(e)) => {
             warning(format!("{},{}", t!("unable_to_verify_updates"), e));
         }
@@ -94,11 +255,193 @@ pub async fn check_for_updates() {
     }
 }
 
+/// Asks the registry for the latest release, then installs it with cargo unless `check_only`.
+pub async fn self_update(source: &Source, check_only: bool) -> anyhow::Result<()> {
+    let release = timeout(
+        Duration::from_secs(10),
+        get_latest_release(source, "salvo-cli"),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!(t!("update_verification_took_long")))?
+    .map_err(|e| anyhow::anyhow!("{e}"))
+    .with_context(|| t!("error_update_check_failed", registry = source.registry))?;
+    if let Some(path) = cache_path() {
+        let check = CachedCheck {
+            registry: source.registry.clone(),
+            checked_at: now(),
+            latest_version: Some(release.version.to_string()),
+        };
+        write_cache(&path, &check);
+    }
+
+    let current_version = env!("CARGO_PKG_VERSION");
+    let status = resolve_update_status(current_version, release.version.clone())?;
+    printer::event(
+        "release",
+        json!({
+            "current_version": current_version,
+            "latest_version": release.version.to_string(),
+            "published": release.published,
+            "rust_version": release.rust_version,
+            "update_available": status != UpdateStatus::UpToDate,
+        }),
+    );
+    success(t!("latest_release", version = release.version));
+    if let Some(published) = &release.published {
+        gray(t!("release_published", date = published));
+    }
+    if let Some(rust_version) = &release.rust_version {
+        gray(t!("release_rust_version", rust_version = rust_version));
+    }
+    if status == UpdateStatus::UpToDate {
+        green(t!("current_version_up_to_date"));
+        return Ok(());
+    }
+    orange(t!(
+        "currently_using_version",
+        current_version = current_version
+    ));
+    if check_only {
+        orange(t!("consider_updating"));
+        return Ok(());
+    }
+
+    success(t!("self_update_installing", version = release.version));
+    let status = Command::new("cargo")
+        .args(["install", "--force", "salvo-cli", "--version"])
+        .arg(release.version.to_string())
+        .status()
+        .with_context(|| t!("error_self_update_failed"))?;
+    if !status.success() {
+        anyhow::bail!(t!("error_self_update_failed"));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::{fs, thread};
+
     use semver::Version;
 
-    use super::{UpdateStatus, parse_skip_update_env, resolve_update_status};
+    use super::{
+        CACHE_TTL, CachedCheck, Release, Source, UpdateStatus, get_latest_release,
+        parse_skip_update_env, read_cache, resolve_update_status, write_cache,
+    };
+    use crate::utils::unique_temp_dir;
+
+    const CRATE_JSON: &str = r#"{
+        "crate": {"name": "salvo-cli", "max_version": "0.4.1"},
+        "versions": [
+            {"num": "0.4.1", "created_at": "2026-09-01T10:00:00Z", "rust_version": "1.89"},
+            {"num": "0.4.0", "created_at": "2026-06-01T10:00:00Z", "rust_version": null}
+        ]
+    }"#;
+
+    /// Stands in for the crates.io API, answering one request and returning its request line.
+    fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            let request = String::from_utf8_lossy(&request).into_owned();
+            request.lines().next().unwrap_or_default().to_owned()
+        });
+        (format!("http://{addr}"), handle)
+    }
+
+    fn source(registry: &str, proxy: Option<&str>) -> Source {
+        Source {
+            registry: registry.to_owned(),
+            proxy: proxy.map(str::to_owned),
+            use_cache: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_reads_the_registry_api() {
+        let (url, server) = serve_once("200 OK", CRATE_JSON);
+        let release = get_latest_release(&source(&format!("{url}/"), None), "salvo-cli")
+            .await
+            .unwrap();
+        assert_eq!(
+            release,
+            Release {
+                version: Version::new(0, 4, 1),
+                published: Some("2026-09-01T10:00:00Z".to_owned()),
+                rust_version: Some("1.89".to_owned()),
+            }
+        );
+        assert_eq!(
+            server.join().unwrap(),
+            "GET /api/v1/crates/salvo-cli HTTP/1.1"
+        );
+
+        let (url, server) = serve_once("404 Not Found", r#"{"errors": []}"#);
+        assert!(
+            get_latest_release(&source(&url, None), "salvo-cli")
+                .await
+                .is_err()
+        );
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release_goes_through_the_configured_proxy() {
+        let (proxy, server) = serve_once("200 OK", CRATE_JSON);
+        let source = source("http://registry.invalid", Some(&proxy));
+        let release = get_latest_release(&source, "salvo-cli").await.unwrap();
+        assert_eq!(release.version, Version::new(0, 4, 1));
+        assert_eq!(
+            server.join().unwrap(),
+            "GET http://registry.invalid/api/v1/crates/salvo-cli HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn test_cached_checks_expire_and_follow_the_registry() {
+        let check = |latest_version: Option<&str>| CachedCheck {
+            registry: "https://crates.io".to_owned(),
+            checked_at: 1_000_000,
+            latest_version: latest_version.map(str::to_owned),
+        };
+        let found = check(Some("0.4.1"));
+        assert!(found.is_fresh("https://crates.io", 1_000_000 + 60));
+        assert!(!found.is_fresh("https://crates.io", 1_000_000 + CACHE_TTL.as_secs()));
+        assert!(!found.is_fresh("https://mirror.example.com", 1_000_060));
+        assert!(
+            !found.is_fresh("https://crates.io", 999_000),
+            "checked in the future"
+        );
+        let failed = check(None);
+        assert!(failed.is_fresh("https://crates.io", 1_000_000 + 60));
+        assert!(!failed.is_fresh("https://crates.io", 1_000_000 + 2 * 60 * 60));
+
+        let dir = unique_temp_dir("updater");
+        let path = dir.join("salvo/update-check.json");
+        assert_eq!(read_cache(&path), None);
+        write_cache(&path, &found);
+        assert_eq!(read_cache(&path), Some(found));
+        fs::write(&path, "{").unwrap();
+        assert_eq!(read_cache(&path), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
 
     #[test]
     fn resolve_update_status_detects_newer_release() {
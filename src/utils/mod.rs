@@ -2,6 +2,10 @@ use std::fs;
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHasher, SaltString};
+use rand::Rng;
+use rand::distr::Alphanumeric;
 
 /// Equivalent to [`create_dir_all`] with better error messages.
 pub fn create_dir_all(p: impl AsRef<Path>) -> Result<()> {
@@ -10,3 +14,60 @@ pub fn create_dir_all(p: impl AsRef<Path>) -> Result<()> {
         .with_context(|| format!("failed to create directory `{}`", p.display()))?;
     Ok(())
 }
+
+/// Generates a random alphanumeric string, safe to embed in URLs and TOML strings.
+pub fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Generates a random version 4 UUID in its hyphenated form.
+pub fn random_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::rng().random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Generates a random MongoDB ObjectId in its hex form.
+pub fn random_object_id() -> String {
+    hex(&rand::rng().random::<[u8; 12]>())
+}
+
+/// Hashes a password the way generated projects verify it, with the default Argon2id parameters.
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::encode_b64(&rand::rng().random::<[u8; 16]>())
+        .map_err(|e| anyhow::anyhow!("failed to hash the password: {e}"))?;
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("failed to hash the password: {e}"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// A path under the temp directory that no other test uses, named after `prefix`.
+#[cfg(test)]
+pub fn unique_temp_dir(prefix: &str) -> std::path::PathBuf {
+    let suffix = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "salvo-cli-{prefix}-{}-{suffix}",
+        std::process::id()
+    ))
+}
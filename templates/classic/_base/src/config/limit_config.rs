@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct LimitConfig {
+    /// Login attempts allowed from one client IP within `login_period`.
+    #[serde(default = "default_login_per_ip")]
+    pub login_per_ip: usize,
+    /// Login attempts allowed for one username within `login_period`.
+    #[serde(default = "default_login_per_username")]
+    pub login_per_username: usize,
+    /// Length of the login rate limiting window, in seconds.
+    #[serde(default = "default_login_period")]
+    pub login_period: i64,
+    /// Maximum accepted request body size, in bytes.
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: u64,
+    /// Time allowed to handle a request before it is aborted, in seconds.
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: u64,
+}
+fn default_login_per_ip() -> usize {
+    20
+}
+fn default_login_per_username() -> usize {
+    5
+}
+fn default_login_period() -> i64 {
+    60
+}
+fn default_max_body_size() -> u64 {
+    1024 * 1024
+}
+fn default_request_timeout() -> u64 {
+    30
+}
+
+impl Default for LimitConfig {
+    fn default() -> Self {
+        Self {
+            login_per_ip: default_login_per_ip(),
+            login_per_username: default_login_per_username(),
+            login_period: default_login_period(),
+            max_body_size: default_max_body_size(),
+            request_timeout: default_request_timeout(),
+        }
+    }
+}
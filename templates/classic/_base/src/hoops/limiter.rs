@@ -0,0 +1,61 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use salvo::prelude::*;
+use salvo::rate_limiter::{
+    BasicQuota, FixedGuard, MokaStore, RateIssuer, RateLimiter, RemoteIpIssuer,
+};
+use salvo::size_limiter::MaxSize;
+use serde::Deserialize;
+
+use crate::config::LimitConfig;
+
+/// Identifies login attempts by the submitted username, so one account can't be
+/// brute forced from many addresses. The key is the username exactly as login
+/// matches it. Falls back to the client IP when the body has no username.
+pub struct LoginUsernameIssuer;
+impl RateIssuer for LoginUsernameIssuer {
+    type Key = String;
+    async fn issue(&self, req: &mut Request, _depot: &Depot) -> Option<Self::Key> {
+        #[derive(Deserialize)]
+        struct LoginName {
+            username: String,
+        }
+        match req.parse_json::<LoginName>().await {
+            Ok(LoginName { username }) => Some(format!("user:{username}")),
+            Err(_) => req.remote_addr().ip().map(|ip| format!("ip:{ip}")),
+        }
+    }
+}
+
+pub fn login_ip_limiter(
+    config: &LimitConfig,
+) -> RateLimiter<FixedGuard, MokaStore<IpAddr, FixedGuard>, RemoteIpIssuer, BasicQuota> {
+    RateLimiter::new(
+        FixedGuard::new(),
+        MokaStore::new(),
+        RemoteIpIssuer,
+        BasicQuota::set_seconds(config.login_per_ip, config.login_period),
+    )
+    .add_headers(true)
+}
+
+pub fn login_username_limiter(
+    config: &LimitConfig,
+) -> RateLimiter<FixedGuard, MokaStore<String, FixedGuard>, LoginUsernameIssuer, BasicQuota> {
+    RateLimiter::new(
+        FixedGuard::new(),
+        MokaStore::new(),
+        LoginUsernameIssuer,
+        BasicQuota::set_seconds(config.login_per_username, config.login_period),
+    )
+    .add_headers(true)
+}
+
+pub fn max_body_size(config: &LimitConfig) -> MaxSize {
+    max_size(config.max_body_size)
+}
+
+pub fn request_timeout(config: &LimitConfig) -> Timeout {
+    Timeout::new(Duration::from_secs(config.request_timeout))
+}
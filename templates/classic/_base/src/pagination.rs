@@ -0,0 +1,129 @@
+//! Query parameters and the response shared by list endpoints.
+use std::str::FromStr;
+
+use salvo::http::StatusError;
+use salvo::oapi::{ToParameters, ToSchema};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// The largest `page_size` a client may ask for.
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Deserialize, Debug, Validate, ToParameters)]
+#[salvo(parameters(default_parameter_in = Query))]
+pub struct PageQuery {
+    /// Page number starting at 1, ignored when `cursor` is set.
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1))]
+    pub current_page: u64,
+    /// Items per page, at most 100.
+    #[serde(default = "default_page_size")]
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE))]
+    pub page_size: u64,
+    /// Field to sort by, prefixed with `-` for descending order.
+    pub sort: Option<String>,
+    /// The `next_cursor` of the previous page, to continue after its last item.
+    pub cursor: Option<String>,
+}
+
+fn default_page() -> u64 {
+    1
+}
+fn default_page_size() -> u64 {
+    10
+}
+
+/// A sort order resolved against the fields an endpoint allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort<T> {
+    pub column: T,
+    pub descending: bool,
+    /// Sorted by the key, the only order cursors work with.
+    by_key: bool,
+}
+
+impl PageQuery {
+    /// Checks the limits and resolves `sort` against `columns`, pairs of a field name and the
+    /// column it sorts by. The first pair is the key, used by default and by cursors.
+    pub fn sort<T: Copy>(&self, columns: &[(&str, T)]) -> Result<Sort<T>, StatusError> {
+        self.validate()
+            .map_err(|e| StatusError::bad_request().brief(e.to_string()))?;
+        let (name, descending) = match self.sort.as_deref() {
+            None | Some("") => (columns[0].0, false),
+            Some(sort) => match sort.strip_prefix('-') {
+                Some(name) => (name, true),
+                None => (sort, false),
+            },
+        };
+        let Some(index) = columns.iter().position(|(field, _)| *field == name) else {
+            let fields = columns.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+            return Err(StatusError::bad_request().brief(format!(
+                "cannot sort by `{name}`, sort by one of: {}",
+                fields.join(", ")
+            )));
+        };
+        if self.cursor.is_some() && index != 0 {
+            return Err(StatusError::bad_request().brief(format!(
+                "`cursor` only works when sorting by `{}`",
+                columns[0].0
+            )));
+        }
+        Ok(Sort {
+            column: columns[index].1,
+            descending,
+            by_key: index == 0,
+        })
+    }
+
+    /// The key to continue after, parsed as the key type.
+    pub fn cursor<K: FromStr>(&self) -> Result<Option<K>, StatusError> {
+        self.cursor
+            .as_deref()
+            .map(|cursor| {
+                cursor
+                    .parse()
+                    .map_err(|_| StatusError::bad_request().brief("invalid `cursor`"))
+            })
+            .transpose()
+    }
+
+    /// Items to skip, none when continuing from a cursor.
+    pub fn offset(&self) -> u64 {
+        if self.cursor.is_some() {
+            0
+        } else {
+            (self.current_page - 1) * self.page_size
+        }
+    }
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Page<T: ToSchema + 'static> {
+    pub data: Vec<T>,
+    pub total: u64,
+    pub current_page: u64,
+    pub page_size: u64,
+    /// Pass as `cursor` to get the items after this page, missing on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T: ToSchema + 'static> Page<T> {
+    /// Wraps the items of one page, `key` gives the cursor pointing after an item.
+    pub fn new<S>(
+        data: Vec<T>,
+        total: u64,
+        query: &PageQuery,
+        sort: &Sort<S>,
+        key: impl Fn(&T) -> String,
+    ) -> Self {
+        let full = data.len() as u64 == query.page_size;
+        let next_cursor = data.last().filter(|_| full && sort.by_key).map(key);
+        Self {
+            data,
+            total,
+            current_page: query.current_page,
+            page_size: query.page_size,
+            next_cursor,
+        }
+    }
+}
@@ -32,11 +32,21 @@ pub async fn init(config: &DbConfig) {
 }
 
 pub fn client() -> &'static Client {
+    #[cfg(test)]
+    if let Some(pool) = crate::test_support::pool() {
+        return pool;
+    }
     MONGODB_CLIENT.get().expect("seaorm pool should set")
 }
 
 pub fn users() -> mongodb::Collection<Document> {
     let config = &crate::config::get().db;
+    #[cfg(test)]
+    if let Some(database) = crate::test_support::database() {
+        return client()
+            .database(&database)
+            .collection::<Document>(&config.collection);
+    }
     client()
         .database(&config.database)
         .collection::<Document>(&config.collection)
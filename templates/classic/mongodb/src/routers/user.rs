@@ -5,11 +5,12 @@ use mongodb::bson::{doc, oid::ObjectId};
 use rinja::Template;
 use salvo::oapi::extract::*;
 use salvo::prelude::*;
-use serde::{Deserialize,Serialize};
+use serde::Deserialize;
 use validator::Validate;
 use crate::hoops::jwt;
 
 use crate::models::SafeUser;
+use crate::pagination::{Page, PageQuery};
 use crate::{db, empty_ok, json_ok, utils, AppResult, EmptyResult, JsonResult};
 
 #[derive(Template)]
@@ -107,43 +108,34 @@ pub async fn delete_user(user_id: PathParam<String>) -> EmptyResult {
     empty_ok()
 }
 
-#[derive(Debug, Deserialize, Validate, Extractible, ToSchema)]
-#[salvo(extract(default_source(from = "query")))]
-pub struct UserListQuery {
-    pub username: Option<String>,
-    #[serde(default = "default_page")]
-    pub current_page: u64,
-    #[serde(default = "default_page_size")]
-    pub page_size: u64,
-}
-
-fn default_page() -> u64 { 1 }
-fn default_page_size() -> u64 { 10 }
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserListResponse {
-    pub data: Vec<SafeUser>,
-    pub total: u64,
-    pub current_page: u64,
-    pub page_size: u64,
-}
+/// Fields the user list sorts by and their document fields, the key first.
+const USER_SORT_FIELDS: [(&str, &str); 2] = [("id", "_id"), ("username", "username")];
 
 #[endpoint(tags("users"))]
-pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<UserListResponse> {
-    let query: UserListQuery = query.extract(depot).await?;
+pub async fn list_users(
+    username: QueryParam<String, false>,
+    query: PageQuery,
+) -> JsonResult<Page<SafeUser>> {
+    let sort = query.sort(&USER_SORT_FIELDS)?;
+    let cursor = query.cursor::<ObjectId>()?;
     let coll_users = db::users();
 
     let mut filter = doc! {};
-    if let Some(username) = &query.username {
+    if let Some(username) = username.into_inner() {
         filter.insert("username", doc! { "$regex": username, "$options": "i" });
     }
-
     let total = coll_users.count_documents(filter.clone()).await?;
+
+    if let Some(cursor) = cursor {
+        let op = if sort.descending { "$lt" } else { "$gt" };
+        filter.insert("_id", doc! { op: cursor });
+    }
     let mut cursor = coll_users
         .find(filter)
-        .await?
-        .skip(((query.current_page - 1) * query.page_size).try_into().unwrap())
-        .take(query.page_size as usize);
+        .sort(doc! { sort.column: if sort.descending { -1 } else { 1 } })
+        .skip(query.offset())
+        .limit(query.page_size as i64)
+        .await?;
 
     let mut users = Vec::new();
     while let Some(result) = cursor.next().await {
@@ -153,10 +145,5 @@ pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<Us
         users.push(SafeUser { id, username });
     }
 
-    json_ok(UserListResponse {
-        data: users,
-        total,
-        current_page: query.current_page,
-        page_size: query.page_size,
-    })
+    json_ok(Page::new(users, total, &query, &sort, |user| user.id.clone()))
 }
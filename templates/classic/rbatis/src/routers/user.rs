@@ -1,14 +1,14 @@
-use rbatis::plugin::page::PageRequest;
 use rbs::value;
 use rinja::Template;
 use salvo::oapi::extract::*;
 use salvo::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use ulid::Ulid;
 use validator::Validate;
 
 use crate::hoops::jwt;
 use crate::models::{SafeUser, User};
+use crate::pagination::{Page, PageQuery};
 use crate::{db, empty_ok, json_ok, utils, AppResult, EmptyResult, JsonResult};
 
 #[derive(Template)]
@@ -110,82 +110,44 @@ pub async fn delete_user(user_id: PathParam<String>) -> EmptyResult {
     empty_ok()
 }
 
-rbatis::pysql_select_page!(select_page() -> User =>
-r#"`select `
-  if do_count == true:
-    `count(1) as count`
-  if do_count == false:
-    `*`
-  ` from users`
-  if do_count == false:
-    ` order by id desc`"#);
-
-rbatis::pysql_select_page!(select_page_by_username(username: &str) -> User =>
-r#"`select `
-  if do_count == true:
-    `count(1) as count`
-  if do_count == false:
-    `*`
-  ` from users where username like #{username}`
-  if do_count == false:
-    ` order by id desc`"#);
-
-#[derive(Debug, Deserialize, Validate, Extractible, ToSchema)]
-#[salvo(extract(default_source(from = "query")))]
-pub struct UserListQuery {
-    pub username: Option<String>,
-    #[serde(default = "default_page")]
-    pub current_page: u64,
-    #[serde(default = "default_page_size")]
-    pub page_size: u64,
-}
-
-fn default_page() -> u64 {
-    1
-}
-fn default_page_size() -> u64 {
-    10
-}
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserListResponse {
-    pub data: Vec<SafeUser>,
-    pub total: i64,
-    pub current_page: u64,
-    pub page_size: u64,
-}
+/// Fields the user list sorts by and their columns, the key first.
+const USER_SORT_FIELDS: [(&str, &str); 2] = [("id", "id"), ("username", "username")];
 
 #[endpoint(tags("users"), status_codes(200, 400))]
-pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<UserListResponse> {
+pub async fn list_users(
+    username: QueryParam<String, false>,
+    query: PageQuery,
+) -> JsonResult<Page<SafeUser>> {
+    let sort = query.sort(&USER_SORT_FIELDS)?;
+    let cursor = query.cursor::<String>()?;
     let rb = db::engine();
-    let query: UserListQuery = query.extract(depot).await?;
-
-    let page_req = PageRequest::new(query.current_page, query.page_size);
-
-    let page = if let Some(username) = query.username {
-        let pattern = format!("%{}%", username);
-        select_page_by_username(rb, &page_req, &pattern)
-            .await
-            .map_err(anyhow::Error::from)?
-    } else {
-        select_page(rb, &page_req)
-            .await
-            .map_err(anyhow::Error::from)?
-    };
+    let like_pattern = format!("%{}%", username.into_inner().unwrap_or_default());
 
-    let safe_users: Vec<SafeUser> = page
-        .records
-        .into_iter()
-        .map(|user| SafeUser {
-            id: user.id,
-            username: user.username,
-        })
-        .collect();
-
-    json_ok(UserListResponse {
-        data: safe_users,
-        total: page.total as i64,
-        current_page: query.current_page,
-        page_size: query.page_size,
-    })
+    let total: u64 = rb
+        .exec_decode(
+            "SELECT COUNT(1) AS count FROM users WHERE username LIKE ?",
+            vec![value!(&like_pattern)],
+        )
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    let mut sql = "SELECT id, username FROM users WHERE username LIKE ?".to_owned();
+    let mut args = vec![value!(&like_pattern)];
+    if let Some(cursor) = cursor {
+        sql.push_str(if sort.descending { " AND id < ?" } else { " AND id > ?" });
+        args.push(value!(cursor));
+    }
+    let direction = if sort.descending { "DESC" } else { "ASC" };
+    sql.push_str(&format!(
+        " ORDER BY {} {direction} LIMIT {} OFFSET {}",
+        sort.column,
+        query.page_size,
+        query.offset()
+    ));
+    let users: Vec<SafeUser> = rb
+        .exec_decode(&sql, args)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    json_ok(Page::new(users, total, &query, &sort, |user| user.id.clone()))
 }
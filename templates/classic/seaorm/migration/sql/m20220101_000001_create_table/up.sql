@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS users
+(
+    id       VARCHAR(36)  PRIMARY KEY NOT NULL,
+    username VARCHAR(255) NOT NULL UNIQUE,
+    password VARCHAR(511) NOT NULL
+);
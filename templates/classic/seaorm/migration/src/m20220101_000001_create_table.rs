@@ -3,55 +3,22 @@ use sea_orm_migration::prelude::*;
 #[derive(DeriveMigrationName)]
 pub struct Migration;
 
+/// The SQL lives in `sql/`, which `salvo new` also builds the SQLite starter database from.
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         manager
-            .create_table(
-                Table::create()
-                    .table(Users::Table)
-                    .if_not_exists()
-                    .col(
-                        ColumnDef::new(Users::Id)
-                            .string()
-                            .not_null()
-                            .primary_key(),
-                    )
-                    .col(
-                        ColumnDef::new(Users::Username)
-                            .string()
-                            .not_null()
-                            .unique_key(),
-                    )
-                    .col(ColumnDef::new(Users::Password).string().not_null())
-                    .to_owned(),
-            )
+            .get_connection()
+            .execute_unprepared(include_str!("../sql/m20220101_000001_create_table/up.sql"))
             .await?;
-
-        let insert = Query::insert()
-            .into_table(Users::Table)
-            .columns([Users::Id, Users::Username, Users::Password])
-            .values_panic([
-                "cdd0e080-5bb1-4442-b6f7-2ba60dbd0555".into(),
-                "zhangsan".into(),
-                "$argon2id$v=19$m=19456,t=2,p=1$rcosL5pOPdA2c7i4ZuLA4Q$s0JGh78UzMmu1qZMpVUA3b8kWYLXcZhw7uBfwhYDJ4A".into(),
-            ])
-            .to_owned();
-
-        manager.exec_stmt(insert).await
+        Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
         manager
-            .drop_table(Table::drop().table(Users::Table).to_owned())
-            .await
+            .get_connection()
+            .execute_unprepared(include_str!("../sql/m20220101_000001_create_table/down.sql"))
+            .await?;
+        Ok(())
     }
 }
-
-#[derive(Iden)]
-enum Users {
-    Table,
-    Id,
-    Username,
-    Password,
-}
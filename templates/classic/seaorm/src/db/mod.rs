@@ -23,5 +23,9 @@ pub async fn init(config: &DbConfig) {
 }
 
 pub fn pool() -> &'static DatabaseConnection {
+    #[cfg(test)]
+    if let Some(pool) = crate::test_support::pool() {
+        return pool;
+    }
     SEAORM_POOL.get().expect("seaorm pool should set")
 }
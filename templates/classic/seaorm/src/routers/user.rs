@@ -1,14 +1,15 @@
 use rinja::Template;
 use salvo::oapi::extract::*;
 use salvo::prelude::*;
-use sea_orm::{ActiveModelTrait, EntityTrait, Set, QueryFilter, QuerySelect, ColumnTrait, PaginatorTrait};
-use serde::{Deserialize, Serialize};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, QueryFilter, QueryOrder, QuerySelect, ColumnTrait, Order, PaginatorTrait};
+use serde::Deserialize;
 use ulid::Ulid;
 use validator::Validate;
 use crate::hoops::jwt;
 
 use crate::entities::{prelude::Users, users};
 use crate::models::SafeUser;
+use crate::pagination::{Page, PageQuery};
 use crate::{db, empty_ok, json_ok, utils, AppResult, EmptyResult, JsonResult};
 
 #[derive(Template)]
@@ -102,45 +103,38 @@ pub async fn delete_user(user_id: PathParam<String>) -> EmptyResult {
     empty_ok()
 }
 
-#[derive(Debug, Deserialize, Validate, Extractible, ToSchema)]
-#[salvo(extract(default_source(from = "query")))]
-pub struct UserListQuery {
-    pub username: Option<String>,
-    #[serde(default = "default_page")]
-    pub current_page: u64,
-    #[serde(default = "default_page_size")]
-    pub page_size: u64,
-}
-
-fn default_page() -> u64 { 1 }
-fn default_page_size() -> u64 { 10 }
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserListResponse {
-    pub data: Vec<SafeUser>,
-    pub total: u64,
-    pub current_page: u64,
-    pub page_size: u64,
-}
+/// Fields the user list sorts by and their columns, the key first.
+const USER_SORT_FIELDS: [(&str, users::Column); 2] = [
+    ("id", users::Column::Id),
+    ("username", users::Column::Username),
+];
 
 #[endpoint(tags("users"))]
-pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<UserListResponse> {
-    let query: UserListQuery = query.extract(depot).await?;
+pub async fn list_users(
+    username: QueryParam<String, false>,
+    query: PageQuery,
+) -> JsonResult<Page<SafeUser>> {
+    let sort = query.sort(&USER_SORT_FIELDS)?;
+    let cursor = query.cursor::<String>()?;
     let conn = db::pool();
-    
+
     let mut select = Users::find();
-    
-    // Apply username filter if provided
-    if let Some(username) = query.username.as_ref() {
+    if let Some(username) = username.into_inner() {
         select = select.filter(users::Column::Username.contains(username));
     }
-    
-    // Get total count
     let total = select.clone().count(conn).await?;
-    
-    // Apply pagination
+
+    if let Some(cursor) = cursor {
+        select = select.filter(if sort.descending {
+            users::Column::Id.lt(cursor)
+        } else {
+            users::Column::Id.gt(cursor)
+        });
+    }
+    let order = if sort.descending { Order::Desc } else { Order::Asc };
     let users = select
-        .offset(((query.current_page - 1) * query.page_size) as u64)
+        .order_by(sort.column, order)
+        .offset(query.offset())
         .limit(query.page_size)
         .all(conn)
         .await?
@@ -150,11 +144,6 @@ pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<Us
             username: user.username,
         })
         .collect::<Vec<_>>();
-    
-    json_ok(UserListResponse {
-        data: users,
-        total,
-        current_page: query.current_page,
-        page_size: query.page_size,
-    })
+
+    json_ok(Page::new(users, total, &query, &sort, |user| user.id.clone()))
 }
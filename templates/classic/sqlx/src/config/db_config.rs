@@ -7,7 +7,7 @@ pub struct DbConfig {
     /// Settings for the primary database. This is usually writable, but will be read-only in
     /// some configurations.
     /// An optional follower database. Always read-only.
-    #[serde(alias = "database_url")]
+    #[serde(alias = "database_url", default)]
     pub url: String,
     #[serde(default = "default_db_pool_size")]
     pub pool_size: u32,
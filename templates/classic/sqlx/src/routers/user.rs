@@ -1,12 +1,14 @@
 use rinja::Template;
 use salvo::oapi::extract::*;
 use salvo::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use sqlx::QueryBuilder;
 use ulid::Ulid;
 use validator::Validate;
 use crate::hoops::jwt;
 
 use crate::models::SafeUser;
+use crate::pagination::{Page, PageQuery};
 use crate::{db, empty_ok, json_ok, utils, AppResult, EmptyResult, JsonResult};
 
 #[derive(Template)]
@@ -117,35 +119,19 @@ pub async fn delete_user(user_id: PathParam<String>) -> EmptyResult {
     empty_ok()
 }
 
-#[derive(Debug, Deserialize, Validate, Extractible, ToSchema)]
-#[salvo(extract(default_source(from = "query")))]
-pub struct UserListQuery {
-    pub username: Option<String>,
-    #[serde(default = "default_page")]
-    pub current_page: i64,
-    #[serde(default = "default_page_size")]
-    pub page_size: i64,
-}
-
-fn default_page() -> i64 { 1 }
-fn default_page_size() -> i64 { 10 }
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserListResponse {
-    pub data: Vec<SafeUser>,
-    pub total: i64,
-    pub current_page: i64,
-    pub page_size: i64,
-}
+/// Fields the user list sorts by and their columns, the key first.
+const USER_SORT_FIELDS: [(&str, &str); 2] = [("id", "id"), ("username", "username")];
 
 #[endpoint(tags("users"))]
-pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<UserListResponse> {
+pub async fn list_users(
+    username: QueryParam<String, false>,
+    query: PageQuery,
+) -> JsonResult<Page<SafeUser>> {
+    let sort = query.sort(&USER_SORT_FIELDS)?;
+    let cursor = query.cursor::<String>()?;
     let conn = db::pool();
-    let query: UserListQuery = query.extract(depot).await?;
-    let username_filter = query.username.clone().unwrap_or_default();
-    let like_pattern = format!("%{}%", username_filter);
-    let offset = (query.current_page - 1) * query.page_size;
-    
+    let like_pattern = format!("%{}%", username.into_inner().unwrap_or_default());
+
     let total = sqlx::query_scalar!(
         r#"
         SELECT COUNT(*) as "count!: i64" FROM users
@@ -155,25 +141,19 @@ pub async fn list_users(query: &mut Request, depot: &mut Depot) -> JsonResult<Us
     )
     .fetch_one(conn)
     .await?;
-    
-    let users = sqlx::query_as!(
-        SafeUser,
-        r#"
-        SELECT id, username FROM users
-        WHERE username LIKE $1
-        LIMIT $2 OFFSET $3
-        "#,
-        like_pattern,
-        query.page_size,
-        offset
-    )
-    .fetch_all(conn)
-    .await?;
-    
-    json_ok(UserListResponse {
-        data: users,
-        total,
-        current_page: query.current_page,
-        page_size: query.page_size,
-    })
+
+    let mut select = QueryBuilder::new("SELECT id, username FROM users WHERE username LIKE ");
+    select.push_bind(&like_pattern);
+    if let Some(cursor) = cursor {
+        select.push(if sort.descending { " AND id < " } else { " AND id > " });
+        select.push_bind(cursor);
+    }
+    let direction = if sort.descending { "DESC" } else { "ASC" };
+    select.push(format_args!(" ORDER BY {} {direction} LIMIT ", sort.column));
+    select.push_bind(query.page_size as i64);
+    select.push(" OFFSET ");
+    select.push_bind(query.offset() as i64);
+    let users = select.build_query_as::<SafeUser>().fetch_all(conn).await?;
+
+    json_ok(Page::new(users, total as u64, &query, &sort, |user| user.id.clone()))
 }
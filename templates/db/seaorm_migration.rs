@@ -0,0 +1,20 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Apply the schema change through the manager, for example:
+        // manager
+        //     .create_table(Table::create().table(Alias::new("posts")).if_not_exists().to_owned())
+        //     .await
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Undo everything that `up` did.
+        Ok(())
+    }
+}